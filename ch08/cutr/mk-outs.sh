@@ -26,4 +26,14 @@ for POS in 1 2 8 1-2 2-3 1-8; do
     cut -c "$POS" "$CSV" >"${OUT_DIR}/$(basename ${CSV}).c${POS}.out"
 done
 
+# 열린 범위
+for FLD in -2 2-; do
+    cut -f "$FLD" "$TSV" >"${OUT_DIR}/$(basename ${TSV}).f${FLD}.out"
+done
+
+for POS in -3 10-; do
+    cut -b "$POS" "$TSV" >"${OUT_DIR}/$(basename ${TSV}).b${POS}.out"
+    cut -c "$POS" "$TSV" >"${OUT_DIR}/$(basename ${TSV}).c${POS}.out"
+done
+
 echo -e "AA\nÉÉ\nSS\nJJ" >"$OUT_DIR/books.c1,1.out"
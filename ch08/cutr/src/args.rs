@@ -1,9 +1,17 @@
 mod arg_range;
+mod csv_dialect;
+mod header_list;
+mod splitter;
 
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, Write};
 
 use arg_range::ArgRangeList;
-use clap::{value_parser, Arg, ArgGroup, Command, Parser};
+use clap::{value_parser, Arg, ArgAction, ArgGroup, Command, Parser};
+use csv::StringRecord;
+use csv_dialect::{parse_byte, CsvDialect, Mode, QuoteStyle};
+use header_list::{extract_indices, HeaderList};
+use regex::Regex;
+use splitter::Splitter;
 
 use crate::file::open;
 
@@ -17,22 +25,39 @@ pub struct Args {
     /// 구분 기호
     #[arg(short, long, value_name = "DELIMITER", default_value = "\t")]
     delimiter: String,
+    /// 필드를 나누는 정규 표현식(출력할 때는 --delimiter로 연결)
+    #[arg(long, value_name = "REGEX", value_parser(value_parser!(Regex)), conflicts_with_all(["bytes", "chars"]))]
+    regex_delimiter: Option<Regex>,
+    /// 필드를 나누는 방법(기본값: 한 바이트 구분 문자는 csv, 그 밖에는 plain)
+    #[arg(long, value_name = "MODE")]
+    mode: Option<Mode>,
+    #[command(flatten)]
+    csv: CsvDialect,
     #[command(flatten)]
     extract: ArgExtract,
+    /// 멀티바이트 문자를 자르지 않음(--bytes와 함께 사용)
+    #[arg(short('n'), conflicts_with_all(["fields", "chars"]))]
+    no_split: bool,
+    /// 첫 줄을 헤더로 사용(--fields, --field-names와 함께 사용)
+    #[arg(long, conflicts_with_all(["bytes", "chars"]))]
+    header: bool,
 }
 
 #[derive(Debug, clap::Args)]
 #[group(required = true)]
 struct ArgExtract {
     /// 선택한 필드
-    #[arg(short, long, value_name = "FIELDS", value_parser(value_parser!(ArgRangeList)))]
+    #[arg(short, long, value_name = "FIELDS", value_parser(value_parser!(ArgRangeList)), allow_hyphen_values(true))]
     fields: Option<ArgRangeList>,
     /// 선택한 바이트
-    #[arg(short, long, value_name = "BYTES", value_parser(value_parser!(ArgRangeList)))]
+    #[arg(short, long, value_name = "BYTES", value_parser(value_parser!(ArgRangeList)), allow_hyphen_values(true))]
     bytes: Option<ArgRangeList>,
     /// 선택한 문자
-    #[arg(short, long, value_name = "CHARS", value_parser(value_parser!(ArgRangeList)))]
+    #[arg(short, long, value_name = "CHARS", value_parser(value_parser!(ArgRangeList)), allow_hyphen_values(true))]
     chars: Option<ArgRangeList>,
+    /// 헤더 이름으로 선택한 필드
+    #[arg(short('F'), long, value_name = "NAMES", value_parser(value_parser!(HeaderList)), requires("header"))]
+    field_names: Option<HeaderList>,
 }
 
 impl Args {
@@ -56,12 +81,79 @@ impl Args {
                     .help("구분 문자")
                     .default_value("\t"),
             )
+            .arg(
+                Arg::new("regex_delimiter")
+                    .long("regex-delimiter")
+                    .value_name("REGEX")
+                    .value_parser(value_parser!(Regex))
+                    .conflicts_with_all(["bytes", "chars"])
+                    .help("필드를 나누는 정규 표현식(출력할 때는 --delimiter로 연결)"),
+            )
+            .arg(
+                Arg::new("mode")
+                    .long("mode")
+                    .value_name("MODE")
+                    .value_parser(value_parser!(Mode))
+                    .help("필드를 나누는 방법(기본값: 한 바이트 구분 문자는 csv, 그 밖에는 plain)"),
+            )
+            .arg(
+                Arg::new("quote")
+                    .long("quote")
+                    .value_name("CHAR")
+                    .value_parser(parse_byte)
+                    .default_value("\"")
+                    .help("따옴표 문자"),
+            )
+            .arg(
+                Arg::new("escape")
+                    .long("escape")
+                    .value_name("CHAR")
+                    .value_parser(parse_byte)
+                    .help("따옴표를 이스케이프하는 문자"),
+            )
+            .arg(
+                Arg::new("no_double_quote")
+                    .long("no-double-quote")
+                    .action(ArgAction::SetTrue)
+                    .help(
+                        "두 번 쓴 따옴표(\"\")를 따옴표 하나로 해석하지 않음(출력할 때는 --escape가 없으면 `\\`로 이스케이프)",
+                    ),
+            )
+            .arg(
+                Arg::new("comment")
+                    .long("comment")
+                    .value_name("CHAR")
+                    .value_parser(parse_byte)
+                    .help("이 문자로 시작하는 줄을 무시"),
+            )
+            .arg(
+                Arg::new("trim")
+                    .long("trim")
+                    .action(ArgAction::SetTrue)
+                    .help("필드 앞뒤의 공백을 제거"),
+            )
+            .arg(
+                Arg::new("flexible")
+                    .long("flexible")
+                    .action(ArgAction::SetTrue)
+                    .help("행마다 필드 수가 달라도 허용"),
+            )
+            .arg(
+                Arg::new("quote_style")
+                    .long("quote-style")
+                    .value_name("STYLE")
+                    .value_parser(value_parser!(QuoteStyle))
+                    .default_value("necessary")
+                    .help("출력할 때 필드를 따옴표로 감싸는 방법"),
+            )
             .arg(
                 Arg::new("fields")
                     .short('f')
                     .long("fields")
                     .value_name("FIELDS")
                     .value_parser(value_parser!(ArgRangeList))
+                    // "-3"과 같은 열린 범위를 옵션으로 해석하지 않도록 한다.
+                    .allow_hyphen_values(true)
                     .help("추출할 필드"),
             )
             .arg(
@@ -70,6 +162,8 @@ impl Args {
                     .long("bytes")
                     .value_name("BYTES")
                     .value_parser(value_parser!(ArgRangeList))
+                    // "-3"과 같은 열린 범위를 옵션으로 해석하지 않도록 한다.
+                    .allow_hyphen_values(true)
                     .help("추출할 바이트 범위"),
             )
             .arg(
@@ -78,11 +172,36 @@ impl Args {
                     .long("chars")
                     .value_name("CHARS")
                     .value_parser(value_parser!(ArgRangeList))
+                    // "-3"과 같은 열린 범위를 옵션으로 해석하지 않도록 한다.
+                    .allow_hyphen_values(true)
                     .help("추출할 문자 범위"),
             )
+            .arg(
+                Arg::new("no_split")
+                    .short('n')
+                    .conflicts_with_all(["fields", "chars"])
+                    .action(ArgAction::SetTrue)
+                    .help("멀티바이트 문자를 자르지 않음(--bytes와 함께 사용)"),
+            )
+            .arg(
+                Arg::new("field_names")
+                    .short('F')
+                    .long("field-names")
+                    .value_name("NAMES")
+                    .value_parser(value_parser!(HeaderList))
+                    .requires("header")
+                    .help("추출할 필드의 헤더 이름(NAME=ALIAS로 별칭 지정)"),
+            )
+            .arg(
+                Arg::new("header")
+                    .long("header")
+                    .conflicts_with_all(["bytes", "chars"])
+                    .action(ArgAction::SetTrue)
+                    .help("첫 줄을 헤더로 사용(--fields, --field-names와 함께 사용)"),
+            )
             .group(
                 ArgGroup::new("arg_extract")
-                    .args(["fields", "bytes", "chars"])
+                    .args(["fields", "bytes", "chars", "field_names"])
                     .required(true), // `.multiple`의 기본값는 `false`이다.
                                      // .multiple(false)
             )
@@ -97,24 +216,55 @@ impl Args {
         let fields = matches.get_one("fields").cloned();
         let bytes = matches.get_one("bytes").cloned();
         let chars = matches.get_one("chars").cloned();
+        let field_names = matches.get_one("field_names").cloned();
         let extract = ArgExtract {
             fields,
             bytes,
             chars,
+            field_names,
         };
 
         Args {
             files,
             delimiter,
+            regex_delimiter: matches.get_one("regex_delimiter").cloned(),
+            mode: matches.get_one("mode").copied(),
+            csv: CsvDialect {
+                // 기본값이 있으므로 `unwrap`을 사용할 수 있다.
+                quote: matches.get_one("quote").copied().unwrap(),
+                escape: matches.get_one("escape").copied(),
+                no_double_quote: matches.get_flag("no_double_quote"),
+                comment: matches.get_one("comment").copied(),
+                trim: matches.get_flag("trim"),
+                flexible: matches.get_flag("flexible"),
+                // 기본값이 있으므로 `unwrap`을 사용할 수 있다.
+                quote_style: matches.get_one("quote_style").copied().unwrap(),
+            },
             extract,
+            no_split: matches.get_flag("no_split"),
+            header: matches.get_flag("header"),
         }
     }
 
     pub fn run(&self) -> Result<(), anyhow::Error> {
-        // 문자열의 길이는 바이트열의 길이이므로 `as_bytes()`를 사용하지 않아도 된다.
         // 파싱한 `self.delimiter`를 점검한다.
-        if self.delimiter.len() != 1 {
-            anyhow::bail!(r#"--delim "{}" must be a single byte"#, self.delimiter);
+        // 한 바이트가 아닌 구분 문자는 `Splitter`가 처리한다.
+        if self.delimiter.is_empty() {
+            anyhow::bail!(r#"--delim "{}" must not be empty"#, self.delimiter);
+        }
+        if self.mode == Some(Mode::Csv) {
+            if self.regex_delimiter.is_some() {
+                anyhow::bail!("--regex-delimiter cannot be used with --mode=csv");
+            }
+            if self.delimiter.len() != 1 {
+                anyhow::bail!(
+                    r#"--delim "{}" must be a single byte with --mode=csv"#,
+                    self.delimiter
+                );
+            }
+        }
+        if self.mode() == Mode::Plain && !self.csv.is_default() {
+            anyhow::bail!("CSV options cannot be used with --mode=plain");
         }
 
         self.files
@@ -134,10 +284,16 @@ impl Args {
         // 다소 중복되는 코드가 있지만 가독성이 나은 것 같다.
         // --bytes를 처리한다.
         if let Some(range) = &self.extract.bytes {
+            let mut stdout = io::stdout().lock();
             file.lines().try_for_each(|line| {
                 let line = line?;
-                let selected = range.extract_bytes(&line);
-                println!("{selected}");
+                // 잘린 멀티바이트 문자를 `U+FFFD`로 바꾸지 않도록 바이트열을 그대로 출력한다.
+                if self.no_split {
+                    stdout.write_all(range.extract_bytes_no_split(&line).as_bytes())?;
+                } else {
+                    stdout.write_all(&range.extract_bytes(&line))?;
+                }
+                stdout.write_all(b"\n")?;
 
                 Ok(())
             })
@@ -150,27 +306,102 @@ impl Args {
 
                 Ok(())
             })
-        // -fields 를 처리한다.
-        } else if let Some(range) = &self.extract.fields {
-            let mut reader = csv::ReaderBuilder::new()
-                // `Args::run`에서 `self.delimiter`에 대한 검사를 하므로 인덱스를 사용해도 문제가 없다.
-                .delimiter(self.delimiter.as_bytes()[0])
-                .has_headers(false)
-                .from_reader(&mut file);
-
-            let mut writer = csv::WriterBuilder::new()
-                // `Args::run`에서 `self.delimiter`에 대한 검사를 하므로 인덱스를 사용해도 문제가 없다.
-                .delimiter(self.delimiter.as_bytes()[0])
-                .from_writer(io::stdout().lock());
-
-            reader.records().try_for_each(|record| {
-                let record = record?;
-                let selected = range.extract_fields(&record);
-                writer.write_record(&selected).map_err(|e| e.into())
-            })
+        // --fields, --field-names 를 처리한다.
+        } else if self.extract.fields.is_some() || self.extract.field_names.is_some() {
+            match self.splitter() {
+                // --mode=csv는 csv 리더를 사용한다.
+                None => {
+                    // `self.splitter()`가 `None`이면 `self.delimiter`는 한 바이트이다.
+                    let delimiter = self.delimiter.as_bytes()[0];
+                    let mut reader = self.csv.reader(delimiter, self.header, &mut file);
+                    let mut writer = self.csv.writer(delimiter, io::stdout().lock());
+
+                    let headers = if self.header {
+                        let headers = reader.headers()?.clone();
+                        // 빈 파일에는 헤더가 없다.
+                        if headers.is_empty() {
+                            return Ok(());
+                        }
+                        Some(headers)
+                    } else {
+                        None
+                    };
+
+                    self.extract_fields_and_write(
+                        headers,
+                        reader.records().map(|record| record.map_err(|e| e.into())),
+                        |selected| writer.write_record(selected).map_err(|e| e.into()),
+                    )
+                }
+                // --mode=plain은 줄 단위로 나눈다.
+                Some(splitter) => {
+                    let mut records = file.lines().map(|line| Ok(splitter.split(&line?)));
+                    let headers = if self.header {
+                        // 빈 파일에는 헤더가 없다.
+                        match records.next().transpose()? {
+                            None => return Ok(()),
+                            headers => headers,
+                        }
+                    } else {
+                        None
+                    };
+
+                    let mut stdout = io::stdout().lock();
+                    self.extract_fields_and_write(headers, records, |selected| {
+                        writeln!(stdout, "{}", selected.join(&self.delimiter)).map_err(|e| e.into())
+                    })
+                }
+            }
         // clap이 정상적으로 작동했다면 도달할 수 없다.
         } else {
-            unreachable!("Must have --fields, --bytes or --chars");
+            unreachable!("Must have --fields, --bytes, --chars or --field-names");
+        }
+    }
+
+    /// `--mode`를 지정하지 않았으면 구분 문자로 정한다.
+    fn mode(&self) -> Mode {
+        match self.mode {
+            Some(mode) => mode,
+            // 문자열의 길이는 바이트열의 길이이므로 `as_bytes()`를 사용하지 않아도 된다.
+            None if self.regex_delimiter.is_none() && self.delimiter.len() == 1 => Mode::Csv,
+            None => Mode::Plain,
+        }
+    }
+
+    /// `--mode=plain`일 때 `Splitter`를 반환한다.
+    /// `--mode=csv`일 때는 csv 리더를 사용하므로 `None`을 반환한다.
+    fn splitter(&self) -> Option<Splitter<'_>> {
+        match (self.mode(), &self.regex_delimiter) {
+            (Mode::Csv, _) => None,
+            (Mode::Plain, Some(re)) => Some(Splitter::Regex(re)),
+            (Mode::Plain, None) => Some(Splitter::Literal(&self.delimiter)),
+        }
+    }
+
+    /// --fields, --field-names로 필드를 선택해서 `write`로 출력한다.
+    /// `headers`가 있으면 헤더 행도 선택해서 출력한다.
+    fn extract_fields_and_write(
+        &self,
+        headers: Option<StringRecord>,
+        mut records: impl Iterator<Item = Result<StringRecord, anyhow::Error>>,
+        mut write: impl FnMut(&[&str]) -> Result<(), anyhow::Error>,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(range) = &self.extract.fields {
+            // 헤더 행도 같은 범위로 잘라서 유지한다.
+            if let Some(headers) = &headers {
+                write(&range.extract_fields(headers))?;
+            }
+
+            records.try_for_each(|record| write(&range.extract_fields(&record?)))
+        } else if let Some(names) = &self.extract.field_names {
+            // `--field-names`는 `--header`가 필요하므로 헤더가 있다.
+            let headers = headers.unwrap_or_default();
+            let indices = names.resolve(&headers)?;
+            write(&names.output_headers())?;
+
+            records.try_for_each(|record| write(&extract_indices(&indices, &record?)))
+        } else {
+            unreachable!("Must have --fields or --field-names");
         }
     }
 }
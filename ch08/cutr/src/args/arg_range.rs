@@ -21,16 +21,39 @@ impl ArgRangeList {
             .collect()
     }
 
-    pub fn extract_bytes(&self, line: &str) -> String {
+    /// 바이트를 그대로 추출한다.
+    /// 멀티바이트 문자가 잘릴 수 있으므로 `String`이 아니라 바이트열을 반환한다.
+    pub fn extract_bytes(&self, line: &str) -> Vec<u8> {
+        let bytes = line.as_bytes();
         self.ranges
             .iter()
-            .map(|range| {
-                let selected = line
-                    .bytes()
+            .flat_map(|range| {
+                bytes
+                    .iter()
                     .skip(range.start)
                     .take(range.end - range.start)
-                    .collect::<Vec<_>>();
-                String::from_utf8_lossy(&selected).to_string()
+                    .copied()
+            })
+            .collect()
+    }
+
+    /// 멀티바이트 문자를 자르지 않고 바이트를 추출한다.(`-n`)
+    /// POSIX의 규칙을 따라서 시작 위치는 그 바이트를 포함하는 문자의 첫 바이트로,
+    /// 끝 위치는 마지막 바이트가 잘리는 문자의 앞 문자까지로 조정한다.
+    pub fn extract_bytes_no_split(&self, line: &str) -> String {
+        let floor_char_boundary = |mut idx: usize| {
+            idx = idx.min(line.len());
+            while !line.is_char_boundary(idx) {
+                idx -= 1;
+            }
+            idx
+        };
+
+        self.ranges
+            .iter()
+            .map(|range| {
+                // `range.start < range.end`이므로 조정한 뒤에도 `start <= end`이다.
+                &line[floor_char_boundary(range.start)..floor_char_boundary(range.end)]
             })
             .collect()
     }
@@ -81,6 +104,7 @@ impl FromStr for ArgRangeList {
 /// 문자열을 `Range<usize>`로 변환하는 가교 역활하는 하는 타입이다.
 /// `TryFrom<&str> for Range<usize>`는 러스트에서 고아규칙에 걸릴 것 같다.(시도 안해봄)
 /// 그리고 약간의 복잡함을 감수하면 비용면에서 큰 차이가 없을 것 같다.
+/// 끝이 열린 범위("N-")는 `end`를 `usize::MAX`로 표현한다.
 #[derive(Clone, Debug)]
 struct ArgRange {
     start: usize,
@@ -111,24 +135,42 @@ impl TryFrom<&str> for ArgRange {
                 .map_err(|_| ArgRangeError::FormatError(s.to_string()))
         };
 
-        let start = match split_value.next() {
-            None => return Err(ArgRangeError::FormatError(s.to_string())),
-            Some(v) => {
-                value_checker(v)?;
-                str_parse(v)?
-            }
-        };
+        // `split`은 최소한 한 번은 값을 반환한다.
+        let start_str = split_value.next().unwrap_or_default();
+        let end_str = split_value.next();
 
-        let end = match split_value.next() {
-            None => start,
-            Some(v) => {
-                value_checker(v)?;
-                let end = str_parse(v)?;
+        let (start, end) = match (start_str, end_str) {
+            // "", "-"는 범위가 아니다.
+            ("", None) | ("", Some("")) => {
+                return Err(ArgRangeError::FormatError(s.to_string()));
+            }
+            // "N"
+            (start, None) => {
+                value_checker(start)?;
+                let start = str_parse(start)?;
+                (start, start)
+            }
+            // "-M": 처음부터 M까지
+            ("", Some(end)) => {
+                value_checker(end)?;
+                (1, str_parse(end)?)
+            }
+            // "N-": N부터 줄의 끝까지
+            (start, Some("")) => {
+                value_checker(start)?;
+                (str_parse(start)?, usize::MAX)
+            }
+            // "N-M"
+            (start, Some(end)) => {
+                value_checker(start)?;
+                value_checker(end)?;
+                let start = str_parse(start)?;
+                let end = str_parse(end)?;
                 if start >= end {
                     return Err(ArgRangeError::ValueError((start, end)));
                 }
 
-                end
+                (start, end)
             }
         };
 
@@ -255,10 +297,6 @@ mod unit_tests {
         let res = ArgRangeList::from_str(v);
         assert!(res.is_err());
 
-        let v = "1-";
-        let res = ArgRangeList::from_str(v);
-        assert!(res.is_err());
-
         let v = "1-1-1";
         let res = ArgRangeList::from_str(v);
         assert!(res.is_err());
@@ -330,6 +368,38 @@ mod unit_tests {
         let res = ArgRangeList::from_str(v);
         assert!(res.is_ok());
         range_compare(res.unwrap(), vec![14..15, 18..20]);
+
+        // 열린 범위도 허용된다.
+        let v = "-3,7";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_ok());
+        range_compare(res.unwrap(), vec![0..3, 6..7]);
+
+        let v = "-1,3";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_ok());
+        range_compare(res.unwrap(), vec![0..1, 2..3]);
+
+        let v = "1,5-";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_ok());
+        range_compare(res.unwrap(), vec![0..1, 4..usize::MAX]);
+
+        let v = "-2,4-";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_ok());
+        range_compare(res.unwrap(), vec![0..2, 3..usize::MAX]);
+
+        // 열린 범위에도 0은 사용할 수 없다.
+        let v = "-0";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_err());
+        pretty_assertions::assert_eq!(res.unwrap_err().to_string(), format_err_string("0"));
+
+        let v = "0-";
+        let res = ArgRangeList::from_str(v);
+        assert!(res.is_err());
+        pretty_assertions::assert_eq!(res.unwrap_err().to_string(), format_err_string("0"));
     }
 
     #[test]
@@ -360,27 +430,76 @@ mod unit_tests {
     #[test]
     fn test_extract_bytes() {
         let abc = "ábc";
-        pretty_assertions::assert_eq!(ArgRangeList::from_str("1").unwrap().extract_bytes(abc), "�");
+        // 멀티바이트 문자가 잘려도 바이트를 그대로 반환한다.
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("1").unwrap().extract_bytes(abc),
+            &abc.as_bytes()[..1]
+        );
         pretty_assertions::assert_eq!(
             ArgRangeList::from_str("1-2").unwrap().extract_bytes(abc),
-            "á"
+            "á".as_bytes()
         );
         pretty_assertions::assert_eq!(
             ArgRangeList::from_str("1-3").unwrap().extract_bytes(abc),
-            "áb"
+            "áb".as_bytes()
         );
         pretty_assertions::assert_eq!(
             ArgRangeList::from_str("1-4").unwrap().extract_bytes(abc),
-            abc
+            abc.as_bytes()
         );
         pretty_assertions::assert_eq!(
             ArgRangeList::from_str("4,3").unwrap().extract_bytes(abc),
-            "cb"
+            "cb".as_bytes()
         );
         pretty_assertions::assert_eq!(
             ArgRangeList::from_str("1-2,6").unwrap().extract_bytes(abc),
+            "á".as_bytes()
+        );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("3-").unwrap().extract_bytes(abc),
+            "bc".as_bytes()
+        );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("-2").unwrap().extract_bytes(abc),
+            "á".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_extract_bytes_no_split() {
+        let abc = "ábc";
+        // 잘리는 문자는 출력하지 않는다.
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("1")
+                .unwrap()
+                .extract_bytes_no_split(abc),
+            ""
+        );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("1-2")
+                .unwrap()
+                .extract_bytes_no_split(abc),
             "á"
         );
+        // 시작 위치가 문자의 중간이면 문자의 처음으로 옮긴다.
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("2-3")
+                .unwrap()
+                .extract_bytes_no_split(abc),
+            "áb"
+        );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("2-")
+                .unwrap()
+                .extract_bytes_no_split(abc),
+            abc
+        );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("4,1-2,9")
+                .unwrap()
+                .extract_bytes_no_split(abc),
+            "cá"
+        );
     }
 
     #[test]
@@ -406,5 +525,9 @@ mod unit_tests {
             ArgRangeList::from_str("2,1").unwrap().extract_fields(&rec),
             &["Sham", "Captain"]
         );
+        pretty_assertions::assert_eq!(
+            ArgRangeList::from_str("2-").unwrap().extract_fields(&rec),
+            &["Sham", "12345"]
+        );
     }
 }
@@ -0,0 +1,139 @@
+use std::io;
+
+use clap::ValueEnum;
+
+/// 필드를 나누는 방법
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Mode {
+    /// 구분 문자로만 나눔(따옴표를 해석하지 않음)
+    Plain,
+    /// RFC 4180 CSV로 해석
+    Csv,
+}
+
+/// 출력할 때 필드를 따옴표로 감싸는 방법
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum QuoteStyle {
+    /// 필요할 때만
+    Necessary,
+    /// 항상
+    Always,
+    /// 하지 않음
+    Never,
+}
+
+impl From<QuoteStyle> for csv::QuoteStyle {
+    fn from(value: QuoteStyle) -> Self {
+        match value {
+            QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+            QuoteStyle::Always => csv::QuoteStyle::Always,
+            QuoteStyle::Never => csv::QuoteStyle::Never,
+        }
+    }
+}
+
+/// `--mode=csv`일 때 사용하는 CSV 옵션
+#[derive(Debug, Clone, clap::Args)]
+pub struct CsvDialect {
+    /// 따옴표 문자
+    #[arg(
+        long,
+        value_name = "CHAR",
+        default_value = "\"",
+        value_parser(parse_byte)
+    )]
+    pub quote: u8,
+    /// 따옴표를 이스케이프하는 문자
+    #[arg(long, value_name = "CHAR", value_parser(parse_byte))]
+    pub escape: Option<u8>,
+    /// 두 번 쓴 따옴표("")를 따옴표 하나로 해석하지 않음(출력할 때는 --escape가 없으면 `\`로 이스케이프)
+    #[arg(long)]
+    pub no_double_quote: bool,
+    /// 이 문자로 시작하는 줄을 무시
+    #[arg(long, value_name = "CHAR", value_parser(parse_byte))]
+    pub comment: Option<u8>,
+    /// 필드 앞뒤의 공백을 제거
+    #[arg(long)]
+    pub trim: bool,
+    /// 행마다 필드 수가 달라도 허용
+    #[arg(long)]
+    pub flexible: bool,
+    /// 출력할 때 필드를 따옴표로 감싸는 방법
+    #[arg(long, value_name = "STYLE", default_value = "necessary")]
+    pub quote_style: QuoteStyle,
+}
+
+impl CsvDialect {
+    /// 기본값과 다른 옵션이 있는지 확인한다.
+    /// `--mode=plain`일 때는 CSV 옵션을 사용할 수 없다.
+    pub fn is_default(&self) -> bool {
+        self.quote == b'"'
+            && self.escape.is_none()
+            && !self.no_double_quote
+            && self.comment.is_none()
+            && !self.trim
+            && !self.flexible
+            && self.quote_style == QuoteStyle::Necessary
+    }
+
+    pub fn reader<R: io::Read>(&self, delimiter: u8, has_headers: bool, rdr: R) -> csv::Reader<R> {
+        csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(has_headers)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(!self.no_double_quote)
+            .comment(self.comment)
+            .trim(if self.trim {
+                csv::Trim::All
+            } else {
+                csv::Trim::None
+            })
+            .flexible(self.flexible)
+            .from_reader(rdr)
+    }
+
+    pub fn writer<W: io::Write>(&self, delimiter: u8, wtr: W) -> csv::Writer<W> {
+        csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .quote(self.quote)
+            // 이스케이프 문자가 있으면 따옴표를 두 번 쓰는 대신 이스케이프한다.
+            // --no-double-quote만 있으면 도움말에 적은 대로 `\`를 사용한다.
+            .double_quote(self.escape.is_none() && !self.no_double_quote)
+            .escape(self.escape.unwrap_or(b'\\'))
+            .flexible(self.flexible)
+            .quote_style(self.quote_style.into())
+            .from_writer(wtr)
+    }
+}
+
+/// 한 바이트 문자를 파싱한다.
+pub fn parse_byte(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(format!(r#""{s}" must be a single byte"#)),
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::parse_byte;
+
+    #[test]
+    fn test_parse_byte() {
+        pretty_assertions::assert_eq!(parse_byte("'"), Ok(b'\''));
+        pretty_assertions::assert_eq!(parse_byte("#"), Ok(b'#'));
+        pretty_assertions::assert_eq!(
+            parse_byte(""),
+            Err(r#""" must be a single byte"#.to_string())
+        );
+        pretty_assertions::assert_eq!(
+            parse_byte("##"),
+            Err("\"##\" must be a single byte".to_string())
+        );
+        pretty_assertions::assert_eq!(
+            parse_byte("é"),
+            Err(r#""é" must be a single byte"#.to_string())
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use csv::StringRecord;
+
+#[derive(Debug, Clone)]
+/// 헤더 이름으로 선택할 필드의 리스트이다.
+pub struct HeaderList {
+    names: Vec<HeaderName>,
+}
+
+/// 선택할 필드의 이름과 출력할 때 사용할 별칭이다.
+/// "NAME=ALIAS"로 별칭을 지정한다.
+#[derive(Debug, Clone, PartialEq)]
+struct HeaderName {
+    name: String,
+    alias: Option<String>,
+}
+
+impl HeaderList {
+    /// 헤더에서 각 이름의 위치를 찾는다.
+    /// 같은 이름이 여러 번 나오면 첫 번째 위치를 사용한다.
+    /// 찾지 못한 이름은 한꺼번에 오류로 반환한다.
+    pub fn resolve(&self, headers: &StringRecord) -> Result<Vec<usize>, HeaderListError> {
+        let mut missing = Vec::new();
+        let indices = self
+            .names
+            .iter()
+            .filter_map(|header_name| {
+                let idx = headers.iter().position(|h| h == header_name.name);
+                if idx.is_none() {
+                    missing.push(header_name.name.clone());
+                }
+                idx
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(HeaderListError::MissingError((
+                missing,
+                headers.iter().map(String::from).collect(),
+            )));
+        }
+
+        Ok(indices)
+    }
+
+    /// 출력할 헤더 행이다. 별칭이 있으면 별칭을 사용한다.
+    pub fn output_headers(&self) -> Vec<&str> {
+        self.names
+            .iter()
+            .map(|header_name| header_name.alias.as_deref().unwrap_or(&header_name.name))
+            .collect()
+    }
+}
+
+/// `resolve`로 찾은 위치의 필드를 추출한다.
+pub fn extract_indices<'a>(indices: &[usize], record: &'a StringRecord) -> Vec<&'a str> {
+    indices
+        .iter()
+        // 행의 길이가 짧을 때는 빈 필드를 출력한다.
+        .map(|&idx| record.get(idx).unwrap_or_default())
+        .collect()
+}
+
+impl TryFrom<&str> for HeaderList {
+    type Error = HeaderListError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let names = s
+            .split(',')
+            .map(|split| {
+                let (name, alias) = match split.split_once('=') {
+                    None => (split, None),
+                    Some((name, alias)) => (name, Some(alias)),
+                };
+                // 이름과 별칭은 비어 있으면 안된다.
+                if name.is_empty() || alias.is_some_and(str::is_empty) {
+                    return Err(HeaderListError::FormatError(split.to_string()));
+                }
+
+                Ok(HeaderName {
+                    name: name.to_string(),
+                    alias: alias.map(String::from),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(HeaderList { names })
+    }
+}
+
+impl FromStr for HeaderList {
+    type Err = HeaderListError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.try_into()
+    }
+}
+
+/// 파싱하거나 헤더를 찾는 과정에서 반환하는 오류이다.
+#[derive(Debug, Clone)]
+pub enum HeaderListError {
+    FormatError(String),
+    /// (찾지 못한 이름, 헤더)
+    MissingError((Vec<String>, Vec<String>)),
+}
+
+impl Error for HeaderListError {}
+
+impl fmt::Display for HeaderListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let quote = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| format!("\"{name}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            HeaderListError::FormatError(value) => write!(f, "illegal field name: \"{}\"", value),
+            HeaderListError::MissingError((missing, headers)) => write!(
+                f,
+                "field name(s) not found in header: {} (available: {})",
+                quote(missing),
+                quote(headers)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::str::FromStr;
+
+    use csv::StringRecord;
+
+    use super::{extract_indices, HeaderList, HeaderName};
+
+    #[test]
+    fn test_parse_names() {
+        // 빈 이름은 오류이다.
+        assert!(HeaderList::from_str("").is_err());
+        assert!(HeaderList::from_str("title,").is_err());
+        assert!(HeaderList::from_str("=Title").is_err());
+        pretty_assertions::assert_eq!(
+            HeaderList::from_str("title=").unwrap_err().to_string(),
+            "illegal field name: \"title=\""
+        );
+
+        let res = HeaderList::from_str("title,year=Year");
+        assert!(res.is_ok());
+        pretty_assertions::assert_eq!(
+            res.unwrap().names,
+            vec![
+                HeaderName {
+                    name: "title".to_string(),
+                    alias: None
+                },
+                HeaderName {
+                    name: "year".to_string(),
+                    alias: Some("Year".to_string())
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let headers = StringRecord::from(vec!["title", "year", "director"]);
+
+        let list = HeaderList::from_str("director,title=Title").unwrap();
+        pretty_assertions::assert_eq!(list.resolve(&headers).unwrap(), vec![2, 0]);
+        pretty_assertions::assert_eq!(list.output_headers(), vec!["director", "Title"]);
+
+        // 찾지 못한 이름은 모두 보고한다.
+        let list = HeaderList::from_str("foo,year,bar").unwrap();
+        pretty_assertions::assert_eq!(
+            list.resolve(&headers).unwrap_err().to_string(),
+            r#"field name(s) not found in header: "foo", "bar" (available: "title", "year", "director")"#
+        );
+    }
+
+    #[test]
+    fn test_extract_indices() {
+        let rec = StringRecord::from(vec!["Captain", "Sham", "12345"]);
+        pretty_assertions::assert_eq!(extract_indices(&[2, 0], &rec), vec!["12345", "Captain"]);
+        pretty_assertions::assert_eq!(extract_indices(&[1, 1], &rec), vec!["Sham", "Sham"]);
+        pretty_assertions::assert_eq!(extract_indices(&[3], &rec), vec![""]);
+    }
+}
@@ -0,0 +1,47 @@
+use csv::StringRecord;
+use regex::Regex;
+
+/// csv 리더를 사용할 수 없는 구분 문자로 줄을 나눈다.
+/// 따옴표는 해석하지 않는다.
+#[derive(Debug, Clone, Copy)]
+pub enum Splitter<'a> {
+    /// 여러 문자로 이루어진 구분 문자
+    Literal(&'a str),
+    /// 정규 표현식 구분 문자(`--regex-delimiter`)
+    Regex(&'a Regex),
+}
+
+impl Splitter<'_> {
+    /// 줄을 나눠서 `ArgRangeList::extract_fields`에 넘길 수 있는 `StringRecord`를 만든다.
+    pub fn split(&self, line: &str) -> StringRecord {
+        match self {
+            Splitter::Literal(delimiter) => line.split(delimiter).collect(),
+            Splitter::Regex(re) => re.split(line).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use csv::StringRecord;
+    use regex::Regex;
+
+    use super::Splitter;
+
+    #[test]
+    fn test_split() {
+        let splitter = Splitter::Literal(" | ");
+        pretty_assertions::assert_eq!(
+            splitter.split("a | b|c | d"),
+            StringRecord::from(vec!["a", "b|c", "d"])
+        );
+        pretty_assertions::assert_eq!(splitter.split(""), StringRecord::from(vec![""]));
+
+        let re = Regex::new(r"\s+").unwrap();
+        let splitter = Splitter::Regex(&re);
+        pretty_assertions::assert_eq!(
+            splitter.split("a  b\t \tc"),
+            StringRecord::from(vec!["a", "b", "c"])
+        );
+    }
+}
@@ -9,6 +9,10 @@ const PRG: &str = "cutr";
 const CSV: &str = "tests/inputs/movies1.csv";
 const TSV: &str = "tests/inputs/movies1.tsv";
 const BOOKS: &str = "tests/inputs/books.tsv";
+const PIPES: &str = "tests/inputs/pipes.txt";
+const SPACES: &str = "tests/inputs/spaces.txt";
+const DIALECT: &str = "tests/inputs/dialect.csv";
+const SINGLE_QUOTE: &str = "tests/inputs/single_quote.csv";
 
 // --------------------------------------------------
 fn random_string() -> String {
@@ -58,7 +62,7 @@ fn dies_not_enough_args() -> Result<()> {
     dies(
         &[CSV],
         "the following required arguments were not provided:\n  \
-        <--fields <FIELDS>|--bytes <BYTES>|--chars <CHARS>>",
+        <--fields <FIELDS>|--bytes <BYTES>|--chars <CHARS>|--field-names <NAMES>>",
     )
 }
 
@@ -97,16 +101,43 @@ fn dies_bad_digit_chars() -> Result<()> {
 fn dies_empty_delimiter() -> Result<()> {
     dies(
         &[CSV, "-f", "1", "-d", ""],
-        r#"--delim "" must be a single byte"#,
+        r#"--delim "" must not be empty"#,
     )
 }
 
 // --------------------------------------------------
 #[test]
-fn dies_bad_delimiter() -> Result<()> {
+fn dies_bad_regex_delimiter() -> Result<()> {
     dies(
-        &[CSV, "-f", "1", "-d", ",,"],
-        r#"--delim ",," must be a single byte"#,
+        &[CSV, "-f", "1", "--regex-delimiter", "("],
+        "invalid value '(' for '--regex-delimiter <REGEX>'",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_csv_mode_multi_char_delimiter() -> Result<()> {
+    dies(
+        &[CSV, "-f", "1", "-d", ",,", "--mode", "csv"],
+        r#"--delim ",," must be a single byte with --mode=csv"#,
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_plain_mode_csv_options() -> Result<()> {
+    dies(
+        &[CSV, "-f", "1", "--mode", "plain", "--trim"],
+        "CSV options cannot be used with --mode=plain",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_quote() -> Result<()> {
+    dies(
+        &[CSV, "-f", "1", "--quote", "''"],
+        r#""''" must be a single byte"#,
     )
 }
 
@@ -173,6 +204,16 @@ fn run_lossy(args: &[&str], expected_file: &str) -> Result<()> {
     Ok(())
 }
 
+// --------------------------------------------------
+fn run_bytes(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn tsv_f1() -> Result<()> {
@@ -278,7 +319,7 @@ fn tsv_b2() -> Result<()> {
 // --------------------------------------------------
 #[test]
 fn tsv_b8() -> Result<()> {
-    run_lossy(&[TSV, "-b", "8"], "tests/expected/movies1.tsv.b8.out")
+    run_bytes(&[TSV, "-b", "8"], "tests/expected/movies1.tsv.b8.out")
 }
 
 // --------------------------------------------------
@@ -296,7 +337,7 @@ fn tsv_b2_3() -> Result<()> {
 // --------------------------------------------------
 #[test]
 fn tsv_b1_8() -> Result<()> {
-    run_lossy(&[TSV, "-b", "1-8"], "tests/expected/movies1.tsv.b1-8.out")
+    run_bytes(&[TSV, "-b", "1-8"], "tests/expected/movies1.tsv.b1-8.out")
 }
 
 // --------------------------------------------------
@@ -340,3 +381,227 @@ fn tsv_c1_8() -> Result<()> {
 fn repeated_value() -> Result<()> {
     run(&[BOOKS, "-c", "1,1"], "tests/expected/books.c1,1.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn tsv_f_open_start() -> Result<()> {
+    run(&[TSV, "-f", "-2"], "tests/expected/movies1.tsv.f-2.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_f_open_end() -> Result<()> {
+    run(&[TSV, "-f", "2-"], "tests/expected/movies1.tsv.f2-.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_b_open_start() -> Result<()> {
+    run_bytes(&[TSV, "-b", "-3"], "tests/expected/movies1.tsv.b-3.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_b_open_end() -> Result<()> {
+    run_bytes(&[TSV, "-b", "10-"], "tests/expected/movies1.tsv.b10-.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_c_open_start() -> Result<()> {
+    run(&[TSV, "-c", "-3"], "tests/expected/movies1.tsv.c-3.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_c_open_end() -> Result<()> {
+    run(&[TSV, "-c", "10-"], "tests/expected/movies1.tsv.c10-.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_b1_8_no_split() -> Result<()> {
+    run(
+        &[TSV, "-n", "-b", "1-8"],
+        "tests/expected/movies1.tsv.b1-8.n.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_split_without_bytes() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args([TSV, "-n", "-c", "1"])
+        .assert()
+        .failure();
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_header_field_names() -> Result<()> {
+    run(
+        &[TSV, "--header", "-F", "year,title"],
+        "tests/expected/movies1.tsv.header.Fyear,title.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_header_field_names_alias() -> Result<()> {
+    run(
+        &[CSV, "--header", "-F", "director=Director,year", "-d", ","],
+        "tests/expected/movies1.csv.header.Fdirector=Director,year.dcomma.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn tsv_header_fields() -> Result<()> {
+    run(
+        &[TSV, "--header", "-f", "1-2"],
+        "tests/expected/movies1.tsv.f1-2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_missing_field_names() -> Result<()> {
+    dies(
+        &[TSV, "--header", "-F", "foo,year,bar"],
+        r#"field name(s) not found in header: "foo", "bar""#,
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_field_names_without_header() -> Result<()> {
+    dies(
+        &[TSV, "-F", "year"],
+        "the following required arguments were not provided:\n  --header",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn multi_char_delimiter() -> Result<()> {
+    run(
+        &[PIPES, "-d", " | ", "-f", "3,1"],
+        "tests/expected/pipes.txt.f3,1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn regex_delimiter() -> Result<()> {
+    run(
+        &[SPACES, "--regex-delimiter", r"\s+", "-d", ",", "-f", "2-"],
+        "tests/expected/spaces.txt.f2-.regex.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_dialect() -> Result<()> {
+    run(
+        &[
+            DIALECT,
+            "-d",
+            ",",
+            "-f",
+            "2,1",
+            "--comment",
+            "#",
+            "--trim",
+            "--flexible",
+        ],
+        "tests/expected/dialect.csv.f2,1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_dialect_quote_always() -> Result<()> {
+    run(
+        &[
+            DIALECT,
+            "-d",
+            ",",
+            "-f",
+            "2,1",
+            "--comment",
+            "#",
+            "--trim",
+            "--flexible",
+            "--quote-style",
+            "always",
+        ],
+        "tests/expected/dialect.csv.f2,1.always.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_dialect_quote_never() -> Result<()> {
+    run(
+        &[
+            DIALECT,
+            "-d",
+            ",",
+            "-f",
+            "2,1",
+            "--comment",
+            "#",
+            "--trim",
+            "--flexible",
+            "--quote-style",
+            "never",
+        ],
+        "tests/expected/dialect.csv.f2,1.never.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_dialect_not_flexible() -> Result<()> {
+    dies(
+        &[DIALECT, "-d", ",", "-f", "1", "--comment", "#"],
+        "found record with 1 fields, but the previous record has 2 fields",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_no_double_quote_escape() -> Result<()> {
+    // --escape가 없으면 도움말에 적은 대로 `\`로 이스케이프한다.
+    for (escape, expected) in [(None, "\"b\\\"c\"\n"), (Some("#"), "\"b#\"c\"\n")] {
+        let mut cmd = Command::cargo_bin(PRG)?;
+        cmd.args(["--mode", "csv", "-d", ",", "-f", "2", "--no-double-quote"]);
+        if let Some(escape) = escape {
+            cmd.args(["--escape", escape]);
+        }
+        cmd.write_stdin("a,b\"c\n")
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn plain_mode() -> Result<()> {
+    run(
+        &[DIALECT, "--mode", "plain", "-d", ",", "-f", "1"],
+        "tests/expected/dialect.csv.f1.plain.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn csv_quote_char() -> Result<()> {
+    run(
+        &[SINGLE_QUOTE, "-d", ",", "-f", "1", "--quote", "'"],
+        "tests/expected/single_quote.csv.f1.out",
+    )
+}
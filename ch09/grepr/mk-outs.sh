@@ -47,3 +47,71 @@ grep -cri the $DIR > "$OUT_DIR/the.recursive.insensitive.count"
 
 # STDIN, 대소문자 무시, 갯수 표시
 cat $DIR/*.txt | grep -ci the - > "$OUT_DIR/the.recursive.insensitive.count.stdin"
+
+# 앞뒤의 줄
+grep -A 1 the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.A1"
+grep -B 2 -i the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.B2.insensitive"
+grep -C 1 --group-separator=XX The $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.capitalized.C1.sepXX"
+grep -C 1 --no-group-separator The $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.capitalized.C1.nosep"
+
+# 앞뒤의 줄, 여러 파일 입력
+grep -A 1 -i to $DIR/nobody.txt $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.to.A1.insensitive"
+
+# 줄 번호, 바이트 오프셋, 파일 이름
+grep -nb -C 1 The $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.the.capitalized.nb.C1"
+grep -H the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.lowercase.H"
+grep -h -i the $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.the.lowercase.insensitive.h"
+
+# 매칭된 부분만
+grep -ob -i the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.lowercase.insensitive.ob"
+
+# 파일 이름만
+grep -l the $DIR/bustle.txt $DIR/empty.txt $DIR/fox.txt > "$OUT_DIR/all.the.lowercase.l"
+grep -L the $DIR/bustle.txt $DIR/empty.txt $DIR/fox.txt > "$OUT_DIR/all.the.lowercase.L"
+
+# 최대 줄 수
+grep -m 1 -A 2 The $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.capitalized.m1.A2"
+
+# 여러 패턴
+grep -e The -e dog $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.the.dog.e"
+grep -f tests/patterns.txt $DIR/*.txt > "$OUT_DIR/all.patterns.f"
+
+# 고정 문자열, 단어, 줄 전체
+grep -F -o 'you?' $DIR/nobody.txt > "$OUT_DIR/nobody.txt.you.F.o"
+grep -w -i the $DIR/*.txt > "$OUT_DIR/all.the.lowercase.insensitive.w"
+grep -x -e 'Until eternity.' -e 'The' $DIR/bustle.txt > "$OUT_DIR/bustle.txt.x"
+grep -v -F -i -e the -e '.' $DIR/*.txt > "$OUT_DIR/all.the.dot.vFi"
+
+# 바이너리 파일, UTF-8이 아닌 입력
+BIN_DIR="tests/binary"
+grep -c fox $BIN_DIR/binary.bin $BIN_DIR/latin1.dat > "$OUT_DIR/binary.fox.count"
+grep -a fox $BIN_DIR/binary.bin > "$OUT_DIR/binary.bin.fox.a"
+grep -c --binary-files=without-match fox $BIN_DIR/binary.bin $BIN_DIR/latin1.dat > "$OUT_DIR/binary.fox.without-match.count"
+grep -n fox $BIN_DIR/latin1.dat > "$OUT_DIR/latin1.dat.fox.n"
+
+# 색
+grep --color=always -n -C 1 The $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.the.capitalized.n.C1.color"
+grep --color=always -v -n -A 1 the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.lowercase.v.n.A1.color"
+grep --color=always -H -ob the $DIR/bustle.txt > "$OUT_DIR/bustle.txt.the.lowercase.H.ob.color"
+grep --color=always -c the $DIR/bustle.txt $DIR/fox.txt > "$OUT_DIR/all.the.lowercase.count.color"
+grep --color=always -l the $DIR/bustle.txt $DIR/empty.txt $DIR/fox.txt > "$OUT_DIR/all.the.lowercase.l.color"
+GREP_COLORS='ms=04;32:fn=01:ln=33:se=35' grep --color=always -Hn fox $DIR/fox.txt > "$OUT_DIR/fox.txt.GREP_COLORS"
+
+# --include, --exclude, --exclude-dir
+TREE_DIR="tests/tree"
+grep -r fox $TREE_DIR > "$OUT_DIR/tree.fox.recursive"
+grep -r --include='*.txt' fox $TREE_DIR > "$OUT_DIR/tree.fox.include"
+grep -r --exclude='*.txt' --exclude='.git*' fox $TREE_DIR > "$OUT_DIR/tree.fox.exclude"
+grep -r --exclude-dir=skip --exclude=ignored.txt fox $TREE_DIR > "$OUT_DIR/tree.fox.exclude-dir"
+grep --include='*.md' fox $TREE_DIR/a.txt $TREE_DIR/b.md > "$OUT_DIR/tree.fox.include.files"
+# GNU grep에는 .gitignore를 따르는 옵션이 없다.
+grep -r fox $TREE_DIR | grep -v ignored.txt > "$OUT_DIR/tree.fox.gitignore"
+
+# Perl 정규식(cargo test --features pcre)
+grep -P -o '(?<=Nobody)\W+\w+' $DIR/nobody.txt > "$OUT_DIR/nobody.txt.P.lookbehind"
+grep -P -i '(\w)\1' $DIR/bustle.txt $DIR/nobody.txt > "$OUT_DIR/all.P.backreference.insensitive"
+
+# NUL 바이트로 끝나는 레코드
+grep -z fox $BIN_DIR/records.dat > "$OUT_DIR/records.dat.fox.z"
+grep -z -n -A 1 fox $BIN_DIR/records.dat > "$OUT_DIR/records.dat.fox.z.n.A1"
+grep -z -o fox $BIN_DIR/records.dat > "$OUT_DIR/records.dat.fox.z.o"
@@ -1,22 +1,77 @@
+mod archive;
+mod colors;
+mod file_filter;
+mod json;
+mod line_finder;
+mod matcher;
+mod ordered;
+mod printer;
+
 use std::{
-    io::BufRead,
-    mem,
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    io::{self, BufRead, BufReader, Cursor, Read, Write},
+    num::NonZero,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Instant,
 };
 
-use clap::{Arg, ArgAction, Command, Parser};
-use regex::{Regex, RegexBuilder};
-use walkdir::WalkDir;
+use clap::{Arg, ArgAction, Command, Parser, parser::ValueSource, value_parser};
+use colors::{ColorChoice, Colors};
+use file_filter::FileFilter;
+use ignore::WalkBuilder;
+use json::{Data, Event, FileStats, LineEvent, Stats, TotalStats};
+use line_finder::{
+    BinaryFiles, Context, FoundLine, LineFinder, Selector, find_lines, is_binary, multiline_spans,
+};
+use matcher::{Matcher, MatcherOptions};
+use ordered::{OrderedOutput, Turn};
+use printer::Printer;
 
 use crate::open;
 
+type SearchResult = Result<SearchOutput, anyhow::Error>;
+
+/// 파일 하나를 검색한 결과
+/// 출력은 `OrderedOutput`에 쓴다.
+struct SearchOutput {
+    searched: Searched,
+    /// --json일 때 요약에 넣을 통계(tar 아카이브는 파일마다 하나씩)
+    stats: Vec<FileStats>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Searched {
+    /// 선택된 줄이 있는지 여부
+    matched: bool,
+    /// 읽는 도중에 오류가 있었는지 여부
+    failed: bool,
+}
+
+/// GNU grep과 같은 종료 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// 선택된 줄이 있음
+    Matched = 0,
+    /// 선택된 줄이 없음
+    NotMatched = 1,
+    /// 오류가 발생함(-q일 때 선택된 줄이 있으면 `Matched`이다.)
+    Error = 2,
+}
+
 #[derive(Debug, Parser)]
-#[command(version, about, author)]
+#[command(version, about, author, disable_help_flag = true)]
 /// `grep`의 러스트 버전
 pub struct Args {
-    /// 검색 패턴
-    #[arg()]
-    pattern: String,
+    /// 검색 패턴(-e, -f가 있으면 첫 번째 파일)
+    #[arg(required_unless_present_any = ["regexp", "pattern_files"])]
+    pattern: Option<String>,
     /// 파일(들)
     #[arg(value_name = "FILE", default_value = "-", num_args(1..))]
     files: Vec<String>,
@@ -32,6 +87,120 @@ pub struct Args {
     /// 매칭되지 않은 줄 수 출력
     #[arg(short = 'v', long = "invert-match", action = ArgAction::SetTrue)]
     invert: bool,
+    /// 매칭된 줄 뒤의 NUM줄 출력
+    #[arg(short = 'A', long, value_name = "NUM")]
+    after_context: Option<usize>,
+    /// 매칭된 줄 앞의 NUM줄 출력
+    #[arg(short = 'B', long, value_name = "NUM")]
+    before_context: Option<usize>,
+    /// 매칭된 줄 앞뒤의 NUM줄 출력
+    #[arg(short = 'C', long, value_name = "NUM")]
+    context: Option<usize>,
+    /// 이어지지 않는 묶음 사이의 구분자
+    #[arg(long, value_name = "SEP", default_value = "--")]
+    group_separator: String,
+    /// 묶음 사이에 구분자를 출력하지 않음
+    #[arg(long, action = ArgAction::SetTrue)]
+    no_group_separator: bool,
+    /// 줄 번호 출력
+    #[arg(short = 'n', long, action = ArgAction::SetTrue)]
+    line_number: bool,
+    /// 바이트 오프셋 출력
+    #[arg(short = 'b', long, action = ArgAction::SetTrue)]
+    byte_offset: bool,
+    /// 파일 이름 출력
+    #[arg(short = 'H', long, action = ArgAction::SetTrue, overrides_with = "no_filename")]
+    with_filename: bool,
+    /// 파일 이름을 출력하지 않음
+    #[arg(short = 'h', long, action = ArgAction::SetTrue, overrides_with = "with_filename")]
+    no_filename: bool,
+    /// 매칭된 부분만 출력
+    #[arg(short = 'o', long, action = ArgAction::SetTrue)]
+    only_matching: bool,
+    /// 매칭된 줄이 있는 파일의 이름만 출력
+    #[arg(short = 'l', long, action = ArgAction::SetTrue, conflicts_with = "files_without_match")]
+    files_with_matches: bool,
+    /// 매칭된 줄이 없는 파일의 이름만 출력
+    #[arg(short = 'L', long, action = ArgAction::SetTrue)]
+    files_without_match: bool,
+    /// NUM줄을 선택한 뒤에 멈춤
+    #[arg(short = 'm', long, value_name = "NUM")]
+    max_count: Option<u64>,
+    /// 검색 패턴(여러 번 사용 가능)
+    #[arg(short = 'e', long, value_name = "PATTERN")]
+    regexp: Vec<String>,
+    /// 파일에서 한 줄에 하나씩 패턴을 읽음
+    #[arg(short = 'f', long = "file", value_name = "FILE")]
+    pattern_files: Vec<String>,
+    /// 패턴을 고정 문자열로 해석
+    #[arg(short = 'F', long, action = ArgAction::SetTrue)]
+    fixed_strings: bool,
+    /// 패턴을 Perl 정규식으로 해석(pcre 기능이 필요함)
+    #[arg(short = 'P', long, action = ArgAction::SetTrue, conflicts_with = "fixed_strings")]
+    perl_regexp: bool,
+    /// 단어 전체가 매칭된 줄만 선택
+    #[arg(short = 'w', long, action = ArgAction::SetTrue)]
+    word_regexp: bool,
+    /// 줄 전체가 매칭된 줄만 선택
+    #[arg(short = 'x', long, action = ArgAction::SetTrue)]
+    line_regexp: bool,
+    /// 줄 대신 NUL 바이트로 끝나는 레코드를 읽고 출력
+    #[arg(short = 'z', long, action = ArgAction::SetTrue)]
+    null_data: bool,
+    /// 여러 줄에 걸쳐 매칭(`.`이 줄바꿈과 매칭됨)
+    #[arg(short = 'U', long, action = ArgAction::SetTrue)]
+    multiline: bool,
+    /// 바이너리 파일을 텍스트 파일처럼 처리(--binary-files=text)
+    #[arg(short = 'a', long, action = ArgAction::SetTrue)]
+    text: bool,
+    /// 바이너리 파일을 처리하는 방법
+    #[arg(long, value_name = "TYPE", default_value = "binary")]
+    binary_files: BinaryFiles,
+    /// 매칭된 부분, 파일 이름, 줄 번호, 구분자에 색을 입힘(GREP_COLORS)
+    #[arg(
+        long,
+        visible_alias = "colour",
+        value_name = "WHEN",
+        default_value = "never",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto"
+    )]
+    color: ColorChoice,
+    /// 이름이 GLOB과 일치하는 파일만 검색
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+    /// 이름이 GLOB과 일치하는 파일은 건너뜀
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// -r일 때 이름이 GLOB과 일치하는 디렉터리는 건너뜀
+    #[arg(long, value_name = "GLOB")]
+    exclude_dir: Vec<String>,
+    /// -r일 때 .gitignore에 있는 파일은 건너뜀
+    #[arg(long, action = ArgAction::SetTrue)]
+    gitignore: bool,
+    /// 동시에 검색할 파일 수(기본값: CPU 수)
+    #[arg(short = 'j', long, value_name = "NUM", value_parser = value_parser!(NonZero<usize>))]
+    threads: Option<NonZero<usize>>,
+    /// gzip, xz, bzip2, zstd로 압축된 파일과 tar 아카이브 안을 검색
+    #[arg(long, action = ArgAction::SetTrue)]
+    search_zip: bool,
+    /// 아무것도 출력하지 않고 처음 선택된 줄에서 멈춤
+    #[arg(short = 'q', long, visible_alias = "silent", action = ArgAction::SetTrue)]
+    quiet: bool,
+    /// 읽을 수 없는 파일에 대한 오류 메시지를 출력하지 않음
+    #[arg(short = 's', long, action = ArgAction::SetTrue)]
+    no_messages: bool,
+    /// 줄마다 JSON 이벤트 하나를 출력
+    #[arg(
+        long,
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["count", "files_with_matches", "files_without_match"]
+    )]
+    json: bool,
+    /// 도움말 출력(-h는 --no-filename이다.)
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
 }
 
 impl Args {
@@ -40,11 +209,13 @@ impl Args {
             .version("0.1.0")
             .author("TestAquatic")
             .about("`grep`의 러스트 버전")
+            // -h는 --no-filename으로 사용한다.
+            .disable_help_flag(true)
             .arg(
                 Arg::new("pattern")
                     .value_name("PATTERN")
-                    .help("검색 패턴")
-                    .required(true),
+                    .help("검색 패턴(-e, -f가 있으면 첫 번째 파일)")
+                    .required_unless_present_any(["regexp", "pattern_files"]),
             )
             .arg(
                 Arg::new("files")
@@ -81,87 +252,929 @@ impl Args {
                     .long("invert-match")
                     .action(ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("after_context")
+                    .help("매칭된 줄 뒤의 NUM줄 출력")
+                    .short('A')
+                    .long("after-context")
+                    .value_name("NUM")
+                    .value_parser(value_parser!(usize)),
+            )
+            .arg(
+                Arg::new("before_context")
+                    .help("매칭된 줄 앞의 NUM줄 출력")
+                    .short('B')
+                    .long("before-context")
+                    .value_name("NUM")
+                    .value_parser(value_parser!(usize)),
+            )
+            .arg(
+                Arg::new("context")
+                    .help("매칭된 줄 앞뒤의 NUM줄 출력")
+                    .short('C')
+                    .long("context")
+                    .value_name("NUM")
+                    .value_parser(value_parser!(usize)),
+            )
+            .arg(
+                Arg::new("group_separator")
+                    .help("이어지지 않는 묶음 사이의 구분자")
+                    .long("group-separator")
+                    .value_name("SEP")
+                    .default_value("--"),
+            )
+            .arg(
+                Arg::new("no_group_separator")
+                    .help("묶음 사이에 구분자를 출력하지 않음")
+                    .long("no-group-separator")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("line_number")
+                    .help("줄 번호 출력")
+                    .short('n')
+                    .long("line-number")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("byte_offset")
+                    .help("바이트 오프셋 출력")
+                    .short('b')
+                    .long("byte-offset")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("with_filename")
+                    .help("파일 이름 출력")
+                    .short('H')
+                    .long("with-filename")
+                    .overrides_with("no_filename")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("no_filename")
+                    .help("파일 이름을 출력하지 않음")
+                    .short('h')
+                    .long("no-filename")
+                    .overrides_with("with_filename")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("only_matching")
+                    .help("매칭된 부분만 출력")
+                    .short('o')
+                    .long("only-matching")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("files_with_matches")
+                    .help("매칭된 줄이 있는 파일의 이름만 출력")
+                    .short('l')
+                    .long("files-with-matches")
+                    .conflicts_with("files_without_match")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("files_without_match")
+                    .help("매칭된 줄이 없는 파일의 이름만 출력")
+                    .short('L')
+                    .long("files-without-match")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("max_count")
+                    .help("NUM줄을 선택한 뒤에 멈춤")
+                    .short('m')
+                    .long("max-count")
+                    .value_name("NUM")
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                Arg::new("regexp")
+                    .help("검색 패턴(여러 번 사용 가능)")
+                    .short('e')
+                    .long("regexp")
+                    .value_name("PATTERN")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("pattern_files")
+                    .help("파일에서 한 줄에 하나씩 패턴을 읽음")
+                    .short('f')
+                    .long("file")
+                    .value_name("FILE")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("fixed_strings")
+                    .help("패턴을 고정 문자열로 해석")
+                    .short('F')
+                    .long("fixed-strings")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("perl_regexp")
+                    .help("패턴을 Perl 정규식으로 해석(pcre 기능이 필요함)")
+                    .short('P')
+                    .long("perl-regexp")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("fixed_strings"),
+            )
+            .arg(
+                Arg::new("word_regexp")
+                    .help("단어 전체가 매칭된 줄만 선택")
+                    .short('w')
+                    .long("word-regexp")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("line_regexp")
+                    .help("줄 전체가 매칭된 줄만 선택")
+                    .short('x')
+                    .long("line-regexp")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("null_data")
+                    .help("줄 대신 NUL 바이트로 끝나는 레코드를 읽고 출력")
+                    .short('z')
+                    .long("null-data")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("multiline")
+                    .help("여러 줄에 걸쳐 매칭(`.`이 줄바꿈과 매칭됨)")
+                    .short('U')
+                    .long("multiline")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("text")
+                    .help("바이너리 파일을 텍스트 파일처럼 처리(--binary-files=text)")
+                    .short('a')
+                    .long("text")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("binary_files")
+                    .help("바이너리 파일을 처리하는 방법")
+                    .long("binary-files")
+                    .value_name("TYPE")
+                    .default_value("binary")
+                    .value_parser(value_parser!(BinaryFiles)),
+            )
+            .arg(
+                Arg::new("color")
+                    .help("매칭된 부분, 파일 이름, 줄 번호, 구분자에 색을 입힘(GREP_COLORS)")
+                    .long("color")
+                    .visible_alias("colour")
+                    .value_name("WHEN")
+                    .default_value("never")
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("auto")
+                    .value_parser(value_parser!(ColorChoice)),
+            )
+            .arg(
+                Arg::new("include")
+                    .help("이름이 GLOB과 일치하는 파일만 검색")
+                    .long("include")
+                    .value_name("GLOB")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("exclude")
+                    .help("이름이 GLOB과 일치하는 파일은 건너뜀")
+                    .long("exclude")
+                    .value_name("GLOB")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("exclude_dir")
+                    .help("-r일 때 이름이 GLOB과 일치하는 디렉터리는 건너뜀")
+                    .long("exclude-dir")
+                    .value_name("GLOB")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("gitignore")
+                    .help("-r일 때 .gitignore에 있는 파일은 건너뜀")
+                    .long("gitignore")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("threads")
+                    .help("동시에 검색할 파일 수(기본값: CPU 수)")
+                    .short('j')
+                    .long("threads")
+                    .value_name("NUM")
+                    .value_parser(value_parser!(NonZero<usize>)),
+            )
+            .arg(
+                Arg::new("search_zip")
+                    .help("gzip, xz, bzip2, zstd로 압축된 파일과 tar 아카이브 안을 검색")
+                    .long("search-zip")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .help("아무것도 출력하지 않고 처음 선택된 줄에서 멈춤")
+                    .short('q')
+                    .long("quiet")
+                    .visible_alias("silent")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("no_messages")
+                    .help("읽을 수 없는 파일에 대한 오류 메시지를 출력하지 않음")
+                    .short('s')
+                    .long("no-messages")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("json")
+                    .help("줄마다 JSON 이벤트 하나를 출력")
+                    .long("json")
+                    .conflicts_with_all(["count", "files_with_matches", "files_without_match"])
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("help")
+                    .help("도움말 출력(-h는 --no-filename이다.)")
+                    .long("help")
+                    .action(ArgAction::Help),
+            )
             .get_matches();
 
+        let regexp: Vec<String> = matches
+            .get_many("regexp")
+            .map(|v| v.cloned().collect())
+            .unwrap_or_default();
+        let pattern_files: Vec<String> = matches
+            .get_many("pattern_files")
+            .map(|v| v.cloned().collect())
+            .unwrap_or_default();
+        let mut pattern: Option<String> = matches.get_one("pattern").cloned();
+        // 기본 인수가 지정되어 있으므로 `unwrap`을 사용할 수 있음
+        let mut files: Vec<String> = matches.get_many("files").unwrap().cloned().collect();
+        // -e, -f가 있으면 첫 번째 위치 인수는 패턴이 아니라 파일이다.
+        if (!regexp.is_empty() || !pattern_files.is_empty())
+            && let Some(file) = pattern.take()
+        {
+            if matches.value_source("files") == Some(ValueSource::DefaultValue) {
+                files.clear();
+            }
+            files.insert(0, file);
+        }
+
         Self {
-            // 반드시 필요한 인수이므로 `unwrap`를 사용할 수 있음
-            pattern: matches.get_one("pattern").cloned().unwrap(),
-            // 기본 인수가 지정되어 있으므로 `unwrap`을 사용할 수 있음
-            files: matches.get_many("files").unwrap().cloned().collect(),
+            pattern,
+            files,
             insensitive: matches.get_flag("insensitive"),
             recursive: matches.get_flag("recursive"),
             count: matches.get_flag("count"),
             invert: matches.get_flag("invert"),
+            after_context: matches.get_one("after_context").copied(),
+            before_context: matches.get_one("before_context").copied(),
+            context: matches.get_one("context").copied(),
+            // 기본 인수가 지정되어 있으므로 `unwrap`을 사용할 수 있음
+            group_separator: matches.get_one("group_separator").cloned().unwrap(),
+            no_group_separator: matches.get_flag("no_group_separator"),
+            line_number: matches.get_flag("line_number"),
+            byte_offset: matches.get_flag("byte_offset"),
+            with_filename: matches.get_flag("with_filename"),
+            no_filename: matches.get_flag("no_filename"),
+            only_matching: matches.get_flag("only_matching"),
+            files_with_matches: matches.get_flag("files_with_matches"),
+            files_without_match: matches.get_flag("files_without_match"),
+            max_count: matches.get_one("max_count").copied(),
+            regexp,
+            pattern_files,
+            fixed_strings: matches.get_flag("fixed_strings"),
+            perl_regexp: matches.get_flag("perl_regexp"),
+            word_regexp: matches.get_flag("word_regexp"),
+            line_regexp: matches.get_flag("line_regexp"),
+            null_data: matches.get_flag("null_data"),
+            multiline: matches.get_flag("multiline"),
+            text: matches.get_flag("text"),
+            // 기본값이 있으므로 `unwrap`을 사용할 수 있다.
+            binary_files: matches.get_one("binary_files").copied().unwrap(),
+            // 기본값이 있으므로 `unwrap`을 사용할 수 있다.
+            color: matches.get_one("color").copied().unwrap(),
+            include: matches
+                .get_many("include")
+                .map(|v| v.cloned().collect())
+                .unwrap_or_default(),
+            exclude: matches
+                .get_many("exclude")
+                .map(|v| v.cloned().collect())
+                .unwrap_or_default(),
+            exclude_dir: matches
+                .get_many("exclude_dir")
+                .map(|v| v.cloned().collect())
+                .unwrap_or_default(),
+            gitignore: matches.get_flag("gitignore"),
+            threads: matches.get_one("threads").copied(),
+            search_zip: matches.get_flag("search_zip"),
+            quiet: matches.get_flag("quiet"),
+            no_messages: matches.get_flag("no_messages"),
+            json: matches.get_flag("json"),
+            help: None,
         }
     }
 
-    fn make_regex(&self) -> Result<regex::Regex, regex::Error> {
-        RegexBuilder::new(&self.pattern)
-            .case_insensitive(self.insensitive)
-            .build()
+    /// -H, -h가 없으면 여러 파일을 검색할 때만 파일 이름을 출력한다.
+    fn show_filename(&self) -> bool {
+        if self.no_filename {
+            false
+        } else {
+            self.with_filename || self.files.len() > 1 || self.recursive
+        }
     }
 
-    pub fn run(&self) -> Result<(), anyhow::Error> {
-        let pattern = self
-            .make_regex()
-            // 잘못된 패턴은 회복할 수 없는 오류이다.
-            .map_err(|_| anyhow::anyhow!(r#"Invalid pattern "{}""#, self.pattern))?;
+    fn printer<'a>(&'a self, colors: Option<&'a Colors>) -> Printer<'a> {
+        Printer {
+            show_filename: self.show_filename(),
+            line_number: self.line_number,
+            byte_offset: self.byte_offset,
+            only_matching: self.only_matching,
+            group_separator: if self.no_group_separator {
+                None
+            } else {
+                Some(&self.group_separator)
+            },
+            colors,
+            null_data: self.null_data,
+        }
+    }
 
-        self.get_bufreader_iter().try_for_each(|file_result| {
-            let (line_result, filename) = match file_result {
-                // 파일을 찾을 수 없을 때는 오류를 인쇄하고 넘어간다.
-                Err(e) => {
-                    eprintln!("{e}");
-                    return Ok(());
+    /// -A, -B, -C 중 하나라도 지정되면 앞뒤의 줄을 출력한다.
+    /// -A, -B는 -C보다 우선한다.
+    fn line_context(&self) -> Option<Context> {
+        if self.after_context.is_none() && self.before_context.is_none() && self.context.is_none() {
+            return None;
+        }
+
+        Some(Context {
+            before: self.before_context.or(self.context).unwrap_or(0),
+            after: self.after_context.or(self.context).unwrap_or(0),
+        })
+    }
+
+    /// -a는 --binary-files=text와 같다.
+    fn binary_files(&self) -> BinaryFiles {
+        if self.text {
+            BinaryFiles::Text
+        } else {
+            self.binary_files
+        }
+    }
+
+    /// -s가 없을 때만 회복할 수 있는 오류를 인쇄한다.
+    fn warn(&self, message: impl Display) {
+        if !self.no_messages {
+            eprintln!("{message}");
+        }
+    }
+
+    fn file_filter(&self) -> Result<FileFilter, anyhow::Error> {
+        FileFilter::new(&self.include, &self.exclude, &self.exclude_dir)
+            .map_err(|glob| anyhow::anyhow!(r#"Invalid glob "{glob}""#))
+    }
+
+    /// -j가 없으면 CPU 수만큼 스레드를 사용한다.
+    fn threads(&self) -> usize {
+        self.threads
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZero::get)
+    }
+
+    /// 위치 인수, -e, -f로 받은 패턴을 모두 모은다.
+    fn patterns(&self) -> Result<Vec<String>, anyhow::Error> {
+        let mut patterns = self
+            .pattern
+            .iter()
+            .chain(self.regexp.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        for filename in &self.pattern_files {
+            let contents = if filename == "-" {
+                io::read_to_string(io::stdin())
+            } else {
+                fs::read_to_string(filename)
+            }
+            .map_err(|e| anyhow::anyhow!("{filename}: {e}"))?;
+            patterns.extend(contents.lines().map(String::from));
+        }
+
+        Ok(patterns)
+    }
+
+    fn make_matcher(&self) -> Result<Matcher, anyhow::Error> {
+        if self.perl_regexp && !cfg!(feature = "pcre") {
+            anyhow::bail!("-P is not supported: build with `--features pcre`");
+        }
+
+        Matcher::new(
+            &self.patterns()?,
+            MatcherOptions {
+                insensitive: self.insensitive,
+                fixed_strings: self.fixed_strings,
+                word: self.word_regexp,
+                line: self.line_regexp,
+                multiline: self.multiline,
+                perl: self.perl_regexp,
+            },
+        )
+        // 잘못된 패턴은 회복할 수 없는 오류이다.
+        .map_err(|pattern| anyhow::anyhow!(r#"Invalid pattern "{pattern}""#))
+    }
+
+    pub fn run(&self) -> Result<ExitStatus, anyhow::Error> {
+        let start = Instant::now();
+        let pattern = self.make_matcher()?;
+        let filter = self.file_filter()?;
+        let colors = self.color.use_color().then(Colors::from_env);
+        let printer = self.printer(colors.as_ref());
+        let files = self.get_file_list(&filter);
+
+        // 작업 스레드는 다음 파일의 인덱스를 가져가서 검색한다.
+        // 차례가 된 파일은 검색하면서 바로 출력하고, 나머지는 차례가 올 때까지 버퍼에 담는다.
+        let next = AtomicUsize::new(0);
+        let turn = Turn::default();
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|s| {
+            for _ in 0..self.threads().min(files.len()) {
+                let tx = tx.clone();
+                let (next, turn, files, pattern, printer) =
+                    (&next, &turn, &files, &pattern, &printer);
+                s.spawn(move || {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(index) else {
+                            break;
+                        };
+                        let mut output = OrderedOutput::new(index, turn, printer);
+                        let result = file.as_ref().map_err(|e| anyhow::anyhow!("{e}")).and_then(
+                            |filename| self.search_file(filename, pattern, printer, &mut output),
+                        );
+                        // 차례가 되었으면 남은 출력을 바로 쓴다.
+                        // 출력 오류는 `output`에 남아서 받는 쪽에서 처리한다.
+                        let _ = output.finish(false);
+                        // 출력하다가 실패해서 받는 쪽이 없어지면 멈춘다.
+                        if tx.send((index, result, output)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            let status = self.print_in_order(rx, &turn, start);
+            // 차례를 기다리는 작업 스레드를 깨워서 끝낸다.
+            turn.close();
+            status
+        })
+    }
+
+    /// 작업 스레드가 보낸 결과를 파일 순서대로 처리하고 다음 파일에 차례를 넘긴다.
+    /// 파일이 끝나기 전에 먼저 출력하지 않으므로 다른 파일의 출력과 섞이지 않는다.
+    fn print_in_order(
+        &self,
+        rx: mpsc::Receiver<(usize, SearchResult, OrderedOutput)>,
+        turn: &Turn,
+        start: Instant,
+    ) -> Result<ExitStatus, anyhow::Error> {
+        let mut matched_any = false;
+        let mut failed_any = false;
+        // --json일 때 마지막에 출력할 파일별 통계
+        let mut files = Vec::new();
+        // 앞의 파일보다 먼저 끝난 파일의 결과를 보관한다.
+        let mut pending = BTreeMap::new();
+        let mut next_index = 0;
+
+        for (index, result, output) in rx {
+            pending.insert(index, (result, output));
+            while let Some((result, mut output)) = pending.remove(&next_index) {
+                next_index += 1;
+                // 차례가 오기 전에 검색을 마친 파일은 버퍼에 남은 출력이 있다.
+                output.finish(true)?;
+                match result {
+                    // 파일을 찾을 수 없을 때는 오류를 인쇄하고 넘어간다.
+                    Err(e) => {
+                        self.warn(e);
+                        failed_any = true;
+                    }
+                    // -q일 때는 처음 선택된 줄에서 멈춘다.
+                    // 받는 쪽이 없어지므로 작업 스레드도 멈춘다.
+                    Ok(SearchOutput { searched, .. }) if self.quiet && searched.matched => {
+                        return Ok(ExitStatus::Matched);
+                    }
+                    Ok(SearchOutput { searched, stats }) => {
+                        matched_any |= searched.matched;
+                        failed_any |= searched.failed;
+                        files.extend(stats);
+                    }
                 }
-                Ok((b_reader, filename)) => (find_lines(b_reader, &pattern, self.invert), filename),
-            };
+                turn.advance();
+            }
+        }
 
-            let finds = match line_result {
-                // 회복할 수 있는 오류이다.
+        if self.json {
+            json::write_event(
+                &mut io::stdout().lock(),
+                &Event::Summary {
+                    elapsed_total: start.elapsed().into(),
+                    stats: TotalStats::new(&files),
+                    files: &files,
+                },
+            )?;
+        }
+
+        Ok(if failed_any {
+            ExitStatus::Error
+        } else if matched_any {
+            ExitStatus::Matched
+        } else {
+            ExitStatus::NotMatched
+        })
+    }
+
+    /// 파일을 열고 검색한 결과를 `output`에 쓴다.
+    fn search_file(
+        &self,
+        filename: &Path,
+        pattern: &Matcher,
+        printer: &Printer,
+        output: &mut OrderedOutput,
+    ) -> SearchResult {
+        let mut b_reader = open(filename)?;
+        if self.search_zip {
+            b_reader = archive::decompress(b_reader)
+                .map_err(|e| anyhow::anyhow!("{}: {e}", filename.display()))?;
+            if archive::is_tar(filename) {
+                return self.search_archive(b_reader, filename, pattern, printer, output);
+            }
+        }
+
+        self.search_reader(b_reader, filename, pattern, printer, output)
+    }
+
+    /// tar 아카이브 안의 파일을 차례대로 검색해서 결과를 합친다.
+    /// 파일 이름은 "아카이브:아카이브 안의 경로"로 출력한다.
+    fn search_archive(
+        &self,
+        b_reader: Box<dyn BufRead>,
+        archive_name: &Path,
+        pattern: &Matcher,
+        printer: &Printer,
+        output: &mut OrderedOutput,
+    ) -> SearchResult {
+        // 아카이브 하나에 파일이 여럿이므로 -h가 없으면 파일 이름을 출력한다.
+        let printer = Printer {
+            show_filename: !self.no_filename,
+            ..*printer
+        };
+        let mut merged = SearchOutput {
+            searched: Searched::default(),
+            stats: Vec::new(),
+        };
+
+        let mut tar = tar::Archive::new(b_reader);
+        let entries = tar
+            .entries()
+            .map_err(|e| anyhow::anyhow!("{}: {e}", archive_name.display()))?;
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                // 아카이브가 손상되면 뒤의 파일은 읽을 수 없다.
                 Err(e) => {
-                    eprintln!("{e}");
-                    return Ok(());
+                    self.warn(format_args!("{}: {e}", archive_name.display()));
+                    merged.searched.failed = true;
+                    break;
                 }
-                Ok(finds) => finds,
             };
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let mut member = archive_name.as_os_str().to_owned();
+            member.push(":");
+            member.push(
+                entry
+                    .path()
+                    .map_err(|e| anyhow::anyhow!("{}: {e}", archive_name.display()))?
+                    .as_os_str(),
+            );
+            let member = PathBuf::from(member);
 
-            let print_filename = || {
-                if self.files.len() > 1 || self.recursive {
-                    print!("{}:", filename.display());
-                }
+            // 아카이브 안의 파일도 압축되어 있을 수 있다.
+            let b_reader = archive::decompress(Box::new(BufReader::new(entry)))
+                .map_err(|e| anyhow::anyhow!("{}: {e}", member.display()))?;
+            let SearchOutput { searched, stats } =
+                self.search_reader(b_reader, &member, pattern, &printer, output)?;
+
+            merged.stats.extend(stats);
+            merged.searched = Searched {
+                matched: merged.searched.matched || searched.matched,
+                failed: merged.searched.failed || searched.failed,
             };
+            // -q일 때는 나머지 파일을 검색하지 않는다.
+            if self.quiet && merged.searched.matched {
+                break;
+            }
+        }
+
+        Ok(merged)
+    }
+
+    /// 이미 연 파일을 검색한 결과를 `output`에 쓴다.
+    fn search_reader(
+        &self,
+        b_reader: Box<dyn BufRead + '_>,
+        filename: &Path,
+        pattern: &Matcher,
+        printer: &Printer,
+        output: &mut OrderedOutput,
+    ) -> SearchResult {
+        // -q는 --json보다 우선한다.
+        if self.json && !self.quiet {
+            let (searched, stats) = self.search_json(b_reader, filename, pattern, output)?;
+            return Ok(SearchOutput {
+                searched,
+                stats: vec![FileStats {
+                    path: Data::from_path(filename),
+                    stats,
+                }],
+            });
+        }
+        let searched = self.search(b_reader, filename, pattern, printer, output)?;
 
-            if self.count {
-                print_filename();
-                println!("{}", finds.len());
-                return Ok(());
+        Ok(SearchOutput {
+            searched,
+            stats: Vec::new(),
+        })
+    }
+
+    /// 바이너리 파일인지 확인하고 줄을 찾기 시작한다.
+    /// 바이너리 파일이면 `true`를 함께 반환한다.
+    fn line_finder<'a>(
+        &self,
+        mut b_reader: Box<dyn BufRead + 'a>,
+        pattern: &'a Matcher,
+        context: Option<Context>,
+    ) -> Result<(LineFinder<'a, Box<dyn BufRead + 'a>>, bool), io::Error> {
+        let binary_files = self.binary_files();
+        let is_binary = match binary_files {
+            // -z일 때는 NUL 바이트가 레코드의 끝이다.
+            _ if self.null_data => false,
+            BinaryFiles::Text => false,
+            _ => is_binary(&mut b_reader)?,
+        };
+        // 바이너리 파일을 매칭되지 않은 것으로 처리할 때는 빈 파일처럼 검색한다.
+        if is_binary && binary_files == BinaryFiles::WithoutMatch {
+            b_reader = Box::new(io::empty());
+        }
+
+        let terminator = if self.null_data { b'\0' } else { b'\n' };
+        // --multiline일 때는 파일 전체를 읽어서 매칭이 걸친 줄을 먼저 찾는다.
+        let selector = if self.multiline {
+            let mut text = Vec::new();
+            b_reader.read_to_end(&mut text)?;
+            let spans = multiline_spans(&text, pattern, terminator);
+            b_reader = Box::new(Cursor::new(text));
+            Selector::Lines(spans)
+        } else {
+            Selector::Pattern(pattern)
+        };
+
+        let finds = find_lines(
+            b_reader,
+            selector,
+            terminator,
+            self.invert,
+            context,
+            self.max_count,
+        );
+
+        Ok((finds, is_binary))
+    }
+
+    /// --json일 때 파일 하나를 검색해서 이벤트를 `writer`에 출력한다.
+    /// 바이너리 파일도 줄을 그대로 출력하며 UTF-8이 아닌 줄은 base64로 인코딩된다.
+    fn search_json(
+        &self,
+        b_reader: Box<dyn BufRead + '_>,
+        filename: &Path,
+        pattern: &Matcher,
+        writer: &mut impl Write,
+    ) -> Result<(Searched, Stats), io::Error> {
+        let start = Instant::now();
+        json::write_event(
+            writer,
+            &Event::Begin {
+                path: Data::from_path(filename),
+            },
+        )?;
+
+        let mut failed = false;
+        let mut matched_lines = 0;
+        let mut bytes_searched = 0;
+        match self.line_finder(b_reader, pattern, self.line_context()) {
+            Ok((mut finds, _)) => {
+                for found in finds.by_ref() {
+                    let event = match found {
+                        Ok(FoundLine::Match(line)) => {
+                            matched_lines += 1;
+                            Event::Match(LineEvent::new(filename, &line, pattern))
+                        }
+                        Ok(FoundLine::Context(line)) => {
+                            Event::Context(LineEvent::new(filename, &line, pattern))
+                        }
+                        // 묶음은 줄 번호로 알 수 있으므로 구분자는 출력하지 않는다.
+                        Ok(FoundLine::Separator) => continue,
+                        Err(e) => {
+                            self.warn(format_args!("{}: {e}", filename.display()));
+                            failed = true;
+                            break;
+                        }
+                    };
+                    json::write_event(writer, &event)?;
+                }
+                bytes_searched = finds.bytes_read();
+            }
+            Err(e) => {
+                self.warn(format_args!("{}: {e}", filename.display()));
+                failed = true;
             }
+        }
+
+        let stats = Stats {
+            matched_lines,
+            bytes_searched,
+            elapsed: start.elapsed().into(),
+        };
+        json::write_event(
+            writer,
+            &Event::End {
+                path: Data::from_path(filename),
+                stats: stats.clone(),
+            },
+        )?;
 
-            finds.iter().for_each(|find| {
-                print_filename();
-                print!("{}", find);
+        let searched = Searched {
+            matched: matched_lines > 0,
+            failed,
+        };
+        Ok((searched, stats))
+    }
+
+    /// 파일 하나를 검색해서 `writer`에 출력한다.
+    /// 읽는 도중에 발생한 오류는 인쇄하고 넘어가며, 출력할 때 발생한 오류만 반환한다.
+    fn search(
+        &self,
+        b_reader: Box<dyn BufRead + '_>,
+        filename: &Path,
+        pattern: &Matcher,
+        printer: &Printer,
+        writer: &mut OrderedOutput,
+    ) -> Result<Searched, io::Error> {
+        // -o일 때는 앞뒤의 줄을 출력하지 않으므로 찾지도 않는다.
+        let context = if self.only_matching {
+            None
+        } else {
+            self.line_context()
+        };
+
+        // 회복할 수 있는 오류이다.
+        let print_error = |e: io::Error, matched: bool| {
+            self.warn(format_args!("{}: {e}", filename.display()));
+            Ok(Searched {
+                matched,
+                failed: true,
+            })
+        };
+
+        let binary_files = self.binary_files();
+        let (mut finds, is_binary) = match self.line_finder(b_reader, pattern, context) {
+            Ok(found) => found,
+            Err(e) => return print_error(e, false),
+        };
+
+        // -q, -l, -L일 때는 첫 번째로 선택된 줄에서 멈춘다.
+        if self.quiet || self.files_with_matches || self.files_without_match {
+            let has_match = match finds.find_map(|found| match found {
+                Ok(FoundLine::Match(_)) => Some(Ok(())),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }) {
+                Some(Err(e)) => return print_error(e, false),
+                Some(Ok(())) => true,
+                None => false,
+            };
+            if !self.quiet && has_match == self.files_with_matches {
+                printer.print_filename(writer, filename)?;
+            }
+            return Ok(Searched {
+                matched: has_match,
+                ..Default::default()
+            });
+        }
+
+        if self.count {
+            let mut count = 0;
+            for found in finds {
+                match found {
+                    Ok(FoundLine::Match(_)) => count += 1,
+                    Ok(_) => {}
+                    Err(e) => return print_error(e, count > 0),
+                }
+            }
+            printer.print_count(writer, filename, count)?;
+            return Ok(Searched {
+                matched: count > 0,
+                ..Default::default()
             });
+        }
+
+        let mut searched = Searched::default();
+        let mut printed_lines = false;
+        for found in finds {
+            let found = match found {
+                Ok(found) => found,
+                Err(e) => {
+                    searched = print_error(e, searched.matched)?;
+                    break;
+                }
+            };
+            searched.matched |= matches!(found, FoundLine::Match(_));
+
+            // 바이너리 파일은 줄 대신 매칭되었다는 사실만 출력한다.
+            // 처음 읽은 버퍼 뒤에서 NUL 바이트를 만날 수도 있으므로 줄마다 확인한다.
+            if binary_files != BinaryFiles::Text {
+                match &found {
+                    FoundLine::Match(line) if is_binary || line.has_nul() => {
+                        if binary_files == BinaryFiles::Binary {
+                            printer.print_binary_matches(writer, filename)?;
+                        }
+                        break;
+                    }
+                    // 바이너리 파일의 앞뒤 줄과 구분자는 출력하지 않는다.
+                    _ if is_binary => continue,
+                    _ => {}
+                }
+            }
 
-            Result::<(), anyhow::Error>::Ok(())
-        })?;
+            // 앞의 파일에서 출력한 묶음과 이 파일의 첫 묶음 사이에 구분자를 넣는다.
+            if !printed_lines && context.is_some() {
+                writer.begin_group();
+            }
+            printed_lines = true;
+            printer.print_found(writer, filename, &found, pattern)?;
+        }
 
-        Ok(())
+        Ok(searched)
     }
 
-    fn get_file_list(&self) -> Vec<Result<PathBuf, anyhow::Error>> {
+    fn get_file_list(&self, filter: &FileFilter) -> Vec<Result<PathBuf, anyhow::Error>> {
         let mut v = Vec::with_capacity(self.files.len());
 
         // -r 일 때
         if self.recursive {
             self.files.iter().for_each(|file| {
-                let file_path = Path::new(file);
-                WalkDir::new(file_path)
-                    .into_iter()
-                    // 파일이 아닌 것은 제거한다.
-                    .flatten()
-                    .filter(|entry| entry.file_type().is_file())
-                    .for_each(|entry| v.push(Ok(entry.path().to_path_buf())))
+                let mut builder = WalkBuilder::new(file);
+                // 숨김 파일도 검색한다.
+                builder.standard_filters(false);
+                if self.gitignore {
+                    builder
+                        .git_ignore(true)
+                        .git_exclude(true)
+                        .git_global(true)
+                        .parents(true)
+                        // Git 저장소가 아니어도 .gitignore를 따른다.
+                        .require_git(false);
+                }
+                let dir_filter = filter.clone();
+                builder.filter_entry(move |entry| {
+                    // 명령행에서 지정한 디렉터리는 제외하지 않는다.
+                    entry.depth() == 0
+                        || !entry.file_type().is_some_and(|t| t.is_dir())
+                        || !dir_filter.is_dir_excluded(entry.path())
+                });
+
+                for entry in builder.build() {
+                    match entry {
+                        // 파일이 아닌 것은 제거한다.
+                        Ok(entry) => {
+                            if entry.file_type().is_some_and(|t| t.is_file())
+                                && filter.is_file_included(entry.path())
+                            {
+                                v.push(Ok(entry.into_path()));
+                            }
+                        }
+                        // 읽을 수 없는 경로도 열 수 없는 파일처럼 알린다.
+                        Err(e) => v.push(Err(walk_error(&e))),
+                    }
+                }
             });
 
             return v;
@@ -175,6 +1188,10 @@ impl Args {
                 v.push(Ok(file_path.to_path_buf()));
                 return;
             }
+            // --include, --exclude는 명령행에서 지정한 파일에도 적용한다.
+            if !filter.is_file_included(file_path) {
+                return;
+            }
             match file_path.metadata() {
                 Ok(metadata) => {
                     if metadata.is_file() {
@@ -204,63 +1221,84 @@ impl Args {
 
         v
     }
-
-    /// `BufRead`의 이터레이터를 산출한다.
-    fn get_bufreader_iter(
-        &self,
-    ) -> impl Iterator<Item = Result<(Box<dyn BufRead>, PathBuf), anyhow::Error>> {
-        self.get_file_list().into_iter().map(|file| {
-            let filename = file?;
-            Ok((open(&filename)?, filename))
-        })
-    }
 }
 
-fn find_lines(
-    mut file: impl BufRead,
-    pattern: &Regex,
-    invert: bool,
-) -> Result<Vec<String>, anyhow::Error> {
-    let mut line = String::new();
-    let mut finds = Vec::new();
-
-    loop {
-        // EOF를 만나면 루프에서 빠져 나온다.
-        if file.read_line(&mut line)? == 0 {
-            break;
-        }
-
-        // `true && false` || `false && true` 일 때
-        if pattern.is_match(&line) ^ invert {
-            // `mem::take`를 사용해서 불필요한 복사를 줄인다.
-            finds.push(mem::take(&mut line));
+/// 디렉터리를 탐색하다 발생한 오류를 파일을 열지 못했을 때와 같은 형식으로 만든다.
+fn walk_error(e: &ignore::Error) -> anyhow::Error {
+    match e {
+        ignore::Error::WithPath { path, err } => match err.io_error() {
+            // walkdir의 오류 메시지에는 경로가 다시 들어 있으므로 원인만 남긴다.
+            Some(io) => {
+                let cause = io
+                    .get_ref()
+                    .and_then(|inner| inner.source())
+                    .and_then(|source| source.downcast_ref::<io::Error>())
+                    .unwrap_or(io);
+                anyhow::anyhow!("{}: {cause}", path.display())
+            }
+            None => anyhow::anyhow!("{}: {err}", path.display()),
+        },
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error(err)
         }
-
-        line.clear();
+        _ => anyhow::anyhow!("{e}"),
     }
-
-    Ok(finds)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use std::io::Cursor;
-
     use rand::distr::{Alphanumeric, SampleString};
-    use regex::{Regex, RegexBuilder};
 
-    use super::{Args, find_lines};
-    const PATH: &'static str = "./tests/inputs/";
+    use super::{Args, BinaryFiles, ColorChoice, FileFilter};
+    const PATH: &str = "./tests/inputs/";
+
+    fn no_filter() -> FileFilter {
+        FileFilter::new(&[], &[], &[]).unwrap()
+    }
 
     fn default_args() -> Args {
         Args {
-            pattern: String::default(),
+            pattern: None,
             files: Vec::new(),
             insensitive: false,
             recursive: false,
             count: false,
             invert: false,
+            after_context: None,
+            before_context: None,
+            context: None,
+            group_separator: "--".to_string(),
+            no_group_separator: false,
+            line_number: false,
+            byte_offset: false,
+            with_filename: false,
+            no_filename: false,
+            only_matching: false,
+            files_with_matches: false,
+            files_without_match: false,
+            max_count: None,
+            regexp: Vec::new(),
+            pattern_files: Vec::new(),
+            fixed_strings: false,
+            perl_regexp: false,
+            word_regexp: false,
+            line_regexp: false,
+            null_data: false,
+            multiline: false,
+            text: false,
+            binary_files: BinaryFiles::Binary,
+            color: ColorChoice::Never,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            exclude_dir: Vec::new(),
+            gitignore: false,
+            threads: None,
+            search_zip: false,
+            quiet: false,
+            no_messages: false,
+            json: false,
+            help: None,
         }
     }
 
@@ -271,7 +1309,7 @@ mod tests {
             files: vec![format!("{PATH}fox.txt")],
             ..default_args()
         }
-        .get_file_list();
+        .get_file_list(&no_filter());
         pretty_assertions::assert_eq!(files.len(), 1);
         pretty_assertions::assert_eq!(
             files[0].as_ref().unwrap().to_string_lossy(),
@@ -283,7 +1321,7 @@ mod tests {
             files: vec![format!("{PATH}")],
             ..default_args()
         }
-        .get_file_list();
+        .get_file_list(&no_filter());
         pretty_assertions::assert_eq!(files.len(), 1);
         if let Err(e) = &files[0] {
             pretty_assertions::assert_eq!(e.to_string(), format!("{} is a directory", PATH));
@@ -295,7 +1333,7 @@ mod tests {
             recursive: true,
             ..default_args()
         }
-        .get_file_list()
+        .get_file_list(&no_filter())
         .iter()
         .map(|r| r.as_ref().unwrap().to_string_lossy().replace("\\", "/"))
         .collect::<Vec<_>>();
@@ -317,40 +1355,8 @@ mod tests {
             files: vec![bad],
             ..default_args()
         }
-        .get_file_list();
+        .get_file_list(&no_filter());
         pretty_assertions::assert_eq!(files.len(), 1);
         assert!(files[0].is_err());
     }
-
-    #[test]
-    fn test_find_lines() {
-        let text = b"Lorem\nIpsum\r\nDOLOR";
-
-        // 패턴 _or_은 "Lorem" 한 줄을 매칭한다.
-        let re1 = Regex::new("or").unwrap();
-        let matches = find_lines(Cursor::new(&text), &re1, false);
-        assert!(matches.is_ok());
-        pretty_assertions::assert_eq!(matches.unwrap(), vec!["Lorem\n"]);
-
-        // 뒤집기가 설정되면 이 함수는 다른 두 줄을 매칭한다.
-        let matches = find_lines(Cursor::new(&text), &re1, true);
-        assert!(matches.is_ok());
-        pretty_assertions::assert_eq!(matches.unwrap(), vec!["Ipsum\r\n", "DOLOR"]);
-
-        // 대소문자를 구문하지 않는 정규식이다.
-        let re2 = RegexBuilder::new("or")
-            .case_insensitive(true)
-            .build()
-            .unwrap();
-
-        // 두줄을 매칭해야 한다.
-        let matches = find_lines(Cursor::new(&text), &re2, false);
-        assert!(matches.is_ok());
-        pretty_assertions::assert_eq!(matches.unwrap(), vec!["Lorem\n", "DOLOR"]);
-
-        // 뒤집기가 설정되면 남은 한줄은 매칭한다.
-        let matches = find_lines(Cursor::new(&text), &re2, true);
-        assert!(matches.is_ok());
-        pretty_assertions::assert_eq!(matches.unwrap(), vec!["Ipsum\r\n"]);
-    }
 }
@@ -0,0 +1,150 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use bzip2::bufread::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+use xz2::bufread::XzDecoder;
+
+/// --search-zip으로 풀 수 있는 압축 형식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Xz,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    /// 확장자가 아니라 파일 앞부분의 매직 바이트로 형식을 판단한다.
+    /// 매직 바이트로 시작하는 텍스트 파일도 있으므로 헤더를 풀 수 없으면 압축되지 않은 파일로 본다.
+    pub fn detect(file: &mut impl BufRead) -> Result<Option<Compression>, io::Error> {
+        let buf = file.fill_buf()?;
+        let compression = match buf {
+            // 압축 방법은 deflate뿐이다.
+            [0x1f, 0x8b, 0x08, ..] => Some(Compression::Gzip),
+            // 스트림 플래그의 첫 바이트는 항상 0이다.
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00, ..] => Some(Compression::Xz),
+            // 매직 바이트 뒤에 블록 크기를 나타내는 숫자가 온다.
+            [b'B', b'Z', b'h', b'1'..=b'9', ..] => Some(Compression::Bzip2),
+            // 프레임 헤더의 예약 비트는 0이다.
+            [0x28, 0xb5, 0x2f, 0xfd, descriptor, ..] if descriptor & 0x08 == 0 => {
+                Some(Compression::Zstd)
+            }
+            _ => None,
+        };
+
+        Ok(compression.filter(|compression| compression.accepts(buf)))
+    }
+
+    /// 버퍼에 있는 앞부분만 풀어 본다.
+    /// 입력이 모자라서 멈춘 것은 헤더를 받아들인 것이다.
+    fn accepts(self, prefix: &[u8]) -> bool {
+        let mut byte = [0];
+        let result = match self {
+            Compression::Gzip => MultiGzDecoder::new(prefix).read(&mut byte),
+            Compression::Xz => XzDecoder::new_multi_decoder(prefix).read(&mut byte),
+            Compression::Bzip2 => MultiBzDecoder::new(prefix).read(&mut byte),
+            Compression::Zstd => zstd::stream::read::Decoder::with_buffer(prefix)
+                .and_then(|mut decoder| decoder.read(&mut byte)),
+        };
+
+        match result {
+            Ok(_) => true,
+            Err(e) => e.kind() == io::ErrorKind::UnexpectedEof,
+        }
+    }
+}
+
+/// 압축된 입력이면 읽으면서 푸는 리더로 감싼다.
+/// 여러 스트림을 이어 붙인 파일(`cat a.gz b.gz`)도 끝까지 읽는다.
+pub fn decompress<'a>(mut file: Box<dyn BufRead + 'a>) -> Result<Box<dyn BufRead + 'a>, io::Error> {
+    let decoded: Box<dyn BufRead + 'a> = match Compression::detect(&mut file)? {
+        Some(Compression::Gzip) => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        Some(Compression::Xz) => Box::new(BufReader::new(XzDecoder::new_multi_decoder(file))),
+        Some(Compression::Bzip2) => Box::new(BufReader::new(MultiBzDecoder::new(file))),
+        Some(Compression::Zstd) => Box::new(BufReader::new(
+            zstd::stream::read::Decoder::with_buffer(file)?,
+        )),
+        None => file,
+    };
+
+    Ok(decoded)
+}
+
+/// 이름으로 tar 아카이브인지 판단한다.
+pub fn is_tar(path: &Path) -> bool {
+    const EXTENSIONS: [&str; 10] = [
+        ".tar", ".tar.gz", ".tgz", ".tar.xz", ".txz", ".tar.bz2", ".tbz2", ".tbz", ".tar.zst",
+        ".tzst",
+    ];
+
+    path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy().to_lowercase();
+        EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, Cursor, Read, Write},
+        path::Path,
+    };
+
+    use flate2::{Compression as Level, write::GzEncoder};
+
+    use super::{Compression, decompress, is_tar};
+
+    #[test]
+    fn test_detect() {
+        let detect = |bytes: &[u8]| Compression::detect(&mut Cursor::new(bytes)).unwrap();
+        pretty_assertions::assert_eq!(detect(b"\x1f\x8b\x08\x00"), Some(Compression::Gzip));
+        pretty_assertions::assert_eq!(detect(b"\xfd7zXZ\x00\x00"), Some(Compression::Xz));
+        pretty_assertions::assert_eq!(detect(b"BZh91AY&SY"), Some(Compression::Bzip2));
+        pretty_assertions::assert_eq!(detect(b"\x28\xb5\x2f\xfd\x24"), Some(Compression::Zstd));
+        pretty_assertions::assert_eq!(detect(b"plain text\n"), None);
+        pretty_assertions::assert_eq!(detect(b""), None);
+        // 매직 바이트로 시작해도 헤더가 맞지 않으면 텍스트 파일이다.
+        pretty_assertions::assert_eq!(detect(b"BZhello world\n"), None);
+        pretty_assertions::assert_eq!(detect(b"BZh9 is a heading\n"), None);
+        pretty_assertions::assert_eq!(detect(b"\x1f\x8bplain\n"), None);
+        pretty_assertions::assert_eq!(detect(b"\x28\xb5\x2f\xfd plain text\n"), None);
+    }
+
+    #[test]
+    fn test_decompress() {
+        // 이어 붙인 gzip 스트림도 모두 푼다.
+        let mut compressed = Vec::new();
+        for text in ["first\n", "second\n"] {
+            let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+            encoder.write_all(text.as_bytes()).unwrap();
+            compressed.extend(encoder.finish().unwrap());
+        }
+        let mut text = String::new();
+        decompress(Box::new(Cursor::new(compressed)))
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        pretty_assertions::assert_eq!(text, "first\nsecond\n");
+
+        // 압축되지 않은 입력은 그대로 읽는다.
+        let lines = decompress(Box::new(Cursor::new(b"plain\ntext\n")))
+            .unwrap()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        pretty_assertions::assert_eq!(lines, ["plain", "text"]);
+    }
+
+    #[test]
+    fn test_is_tar() {
+        assert!(is_tar(Path::new("logs/archive.tar.gz")));
+        assert!(is_tar(Path::new("ARCHIVE.TGZ")));
+        assert!(is_tar(Path::new("a.tar")));
+        assert!(is_tar(Path::new("a.tar.zst")));
+        assert!(!is_tar(Path::new("app.log.gz")));
+        assert!(!is_tar(Path::new("tar")));
+    }
+}
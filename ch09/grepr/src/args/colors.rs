@@ -0,0 +1,127 @@
+use std::io::{self, IsTerminal, Write};
+
+use clap::ValueEnum;
+
+/// 색을 사용할 때(--color)
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// 표준 출력이 터미널일 때만
+    Auto,
+    /// 항상
+    Always,
+    /// 사용하지 않음
+    #[default]
+    Never,
+}
+
+impl ColorChoice {
+    pub fn use_color(self) -> bool {
+        match self {
+            ColorChoice::Auto => io::stdout().is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// 각 부분에 사용할 SGR 매개변수이다.
+/// 빈 문자열이면 색을 입히지 않는다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    /// 매칭된 부분(ms=)
+    pub matched: String,
+    /// 파일 이름(fn=)
+    pub filename: String,
+    /// 줄 번호(ln=)
+    pub line_number: String,
+    /// 바이트 오프셋(bn=)
+    pub byte_offset: String,
+    /// 구분자(se=)
+    pub separator: String,
+}
+
+impl Default for Colors {
+    /// GNU grep의 기본값이다.
+    fn default() -> Self {
+        Colors {
+            matched: "01;31".to_string(),
+            filename: "35".to_string(),
+            line_number: "32".to_string(),
+            byte_offset: "32".to_string(),
+            separator: "36".to_string(),
+        }
+    }
+}
+
+impl Colors {
+    /// `GREP_COLORS` 환경 변수를 읽는다. 없으면 기본값을 사용한다.
+    pub fn from_env() -> Self {
+        std::env::var("GREP_COLORS")
+            .map(|value| Colors::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// "ms=01;31:fn=35"처럼 `:`로 구분된 목록을 해석한다.
+    /// 알 수 없는 항목은 무시한다.
+    pub fn parse(value: &str) -> Self {
+        let mut colors = Colors::default();
+        for item in value.split(':') {
+            let Some((name, sgr)) = item.split_once('=') else {
+                continue;
+            };
+            let sgr = sgr.to_string();
+            match name {
+                // mt=는 ms=와 mc=를 함께 지정한다.
+                "mt" | "ms" => colors.matched = sgr,
+                "fn" => colors.filename = sgr,
+                "ln" => colors.line_number = sgr,
+                "bn" => colors.byte_offset = sgr,
+                "se" => colors.separator = sgr,
+                _ => {}
+            }
+        }
+
+        colors
+    }
+}
+
+/// `text`를 `sgr` 색으로 출력한다.
+pub fn paint(writer: &mut impl Write, sgr: &str, text: &[u8]) -> Result<(), io::Error> {
+    if sgr.is_empty() {
+        return writer.write_all(text);
+    }
+    write!(writer, "\x1b[{sgr}m\x1b[K")?;
+    writer.write_all(text)?;
+    write!(writer, "\x1b[m\x1b[K")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Colors, paint};
+
+    #[test]
+    fn test_parse() {
+        pretty_assertions::assert_eq!(Colors::parse(""), Colors::default());
+
+        let colors = Colors::parse("ms=04;32:fn=:ln=33:xx=1:se");
+        pretty_assertions::assert_eq!(
+            colors,
+            Colors {
+                matched: "04;32".to_string(),
+                filename: "".to_string(),
+                line_number: "33".to_string(),
+                ..Default::default()
+            }
+        );
+
+        pretty_assertions::assert_eq!(Colors::parse("mt=7").matched, "7");
+    }
+
+    #[test]
+    fn test_paint() {
+        let mut buf = Vec::new();
+        paint(&mut buf, "01;31", b"the").unwrap();
+        paint(&mut buf, "", b" end").unwrap();
+        pretty_assertions::assert_eq!(buf, b"\x1b[01;31m\x1b[Kthe\x1b[m\x1b[K end");
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// --include, --exclude, --exclude-dir로 검색할 파일을 고른다.
+/// GNU grep처럼 경로가 아니라 이름만 비교한다.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    /// 비어 있으면 모든 파일을 검색한다.
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    exclude_dir: GlobSet,
+}
+
+impl FileFilter {
+    /// 잘못된 glob이 있으면 그 glob을 오류로 반환한다.
+    pub fn new(
+        include: &[String],
+        exclude: &[String],
+        exclude_dir: &[String],
+    ) -> Result<FileFilter, String> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_glob_set(include)?)
+        };
+
+        Ok(FileFilter {
+            include,
+            exclude: build_glob_set(exclude)?,
+            exclude_dir: build_glob_set(exclude_dir)?,
+        })
+    }
+
+    pub fn is_file_included(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return true;
+        };
+
+        !self.exclude.is_match(name)
+            && self
+                .include
+                .as_ref()
+                .is_none_or(|include| include.is_match(name))
+    }
+
+    pub fn is_dir_excluded(&self, path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| self.exclude_dir.is_match(name))
+    }
+}
+
+fn build_glob_set(globs: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).map_err(|_| glob.clone())?);
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::FileFilter;
+
+    fn globs(globs: &[&str]) -> Vec<String> {
+        globs.iter().map(|g| g.to_string()).collect()
+    }
+
+    #[test]
+    fn test_file_filter() {
+        // 아무것도 지정하지 않으면 모두 검색한다.
+        let filter = FileFilter::new(&[], &[], &[]).unwrap();
+        assert!(filter.is_file_included(Path::new("a/b.txt")));
+        assert!(!filter.is_dir_excluded(Path::new("a/.git")));
+
+        let filter = FileFilter::new(
+            &globs(&["*.txt", "*.md"]),
+            &globs(&["empty*"]),
+            &globs(&[".git", "target"]),
+        )
+        .unwrap();
+        // 경로가 아니라 이름을 비교한다.
+        assert!(filter.is_file_included(Path::new("src.rs/fox.txt")));
+        assert!(filter.is_file_included(Path::new("README.md")));
+        assert!(!filter.is_file_included(Path::new("main.rs")));
+        // --exclude가 --include보다 우선한다.
+        assert!(!filter.is_file_included(Path::new("tests/empty.txt")));
+        assert!(filter.is_dir_excluded(Path::new("repo/.git")));
+        assert!(filter.is_dir_excluded(Path::new("target")));
+        assert!(!filter.is_dir_excluded(Path::new("target2")));
+
+        pretty_assertions::assert_eq!(
+            FileFilter::new(&globs(&["a[b"]), &[], &[]).unwrap_err(),
+            "a[b"
+        );
+    }
+}
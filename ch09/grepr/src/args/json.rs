@@ -0,0 +1,218 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde::Serialize;
+
+use super::{line_finder::Line, matcher::Matcher};
+
+/// --json일 때 한 줄에 하나씩 출력하는 이벤트이다.
+/// `{"type": "match", "data": {...}}` 형식이다.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// 파일 검색을 시작함
+    Begin { path: Data },
+    /// 선택된 줄
+    Match(LineEvent),
+    /// 선택된 줄 앞뒤의 줄
+    Context(LineEvent),
+    /// 파일 검색을 마침
+    End { path: Data, stats: Stats },
+    /// 모든 검색을 마침
+    Summary {
+        elapsed_total: Elapsed,
+        stats: TotalStats,
+        files: &'a [FileStats],
+    },
+}
+
+/// UTF-8이면 `{"text": ...}`, 아니면 base64로 인코딩한 `{"bytes": ...}`이다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Data {
+    Text { text: String },
+    Bytes { bytes: String },
+}
+
+impl Data {
+    pub fn new(bytes: &[u8]) -> Data {
+        match str::from_utf8(bytes) {
+            Ok(text) => Data::Text {
+                text: text.to_string(),
+            },
+            Err(_) => Data::Bytes {
+                bytes: BASE64_STANDARD.encode(bytes),
+            },
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Data {
+        Data::new(path.as_os_str().as_encoded_bytes())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LineEvent {
+    path: Data,
+    /// 줄 끝의 구분 바이트를 포함한다.
+    lines: Data,
+    line_number: u64,
+    /// 파일의 처음부터 이 줄까지의 바이트 수
+    absolute_offset: u64,
+    submatches: Vec<SubMatch>,
+}
+
+impl LineEvent {
+    pub fn new(path: &Path, line: &Line, pattern: &Matcher) -> LineEvent {
+        let content = line.content();
+        LineEvent {
+            path: Data::from_path(path),
+            lines: Data::new(&line.text),
+            line_number: line.number,
+            absolute_offset: line.offset,
+            submatches: pattern
+                .find_iter(content)
+                .into_iter()
+                .filter(|range| !range.is_empty())
+                .map(|range| SubMatch {
+                    matched: Data::new(&content[range.clone()]),
+                    start: range.start,
+                    end: range.end,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 줄 안에서 매칭된 부분과 줄의 처음부터의 바이트 위치이다.
+#[derive(Debug, Serialize)]
+pub struct SubMatch {
+    #[serde(rename = "match")]
+    matched: Data,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Elapsed {
+    secs: u64,
+    nanos: u32,
+    human: String,
+}
+
+impl From<Duration> for Elapsed {
+    fn from(duration: Duration) -> Self {
+        Elapsed {
+            secs: duration.as_secs(),
+            nanos: duration.subsec_nanos(),
+            human: format!("{:.6}s", duration.as_secs_f64()),
+        }
+    }
+}
+
+/// 파일 하나의 검색 결과
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub matched_lines: u64,
+    pub bytes_searched: u64,
+    pub elapsed: Elapsed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStats {
+    pub path: Data,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+/// 모든 파일의 검색 결과를 합친 것이다.
+#[derive(Debug, Default, Serialize)]
+pub struct TotalStats {
+    pub searches: u64,
+    pub searches_with_match: u64,
+    pub matched_lines: u64,
+    pub bytes_searched: u64,
+}
+
+impl TotalStats {
+    pub fn new(files: &[FileStats]) -> TotalStats {
+        files
+            .iter()
+            .fold(TotalStats::default(), |total, file| TotalStats {
+                searches: total.searches + 1,
+                searches_with_match: total.searches_with_match
+                    + (file.stats.matched_lines > 0) as u64,
+                matched_lines: total.matched_lines + file.stats.matched_lines,
+                bytes_searched: total.bytes_searched + file.stats.bytes_searched,
+            })
+    }
+}
+
+/// 이벤트를 JSON 한 줄로 출력한다.
+pub fn write_event(writer: &mut impl Write, event: &Event) -> Result<(), io::Error> {
+    serde_json::to_writer(&mut *writer, event)?;
+    writeln!(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use regex::bytes::Regex;
+
+    use super::{Data, Event, LineEvent, Stats, write_event};
+    use crate::args::{line_finder::Line, matcher::Matcher};
+
+    #[test]
+    fn test_write_event() {
+        let re = Matcher::Regex(Regex::new("o+").unwrap());
+        let line = Line {
+            number: 2,
+            offset: 8,
+            text: b"foo boo\n".to_vec(),
+            terminator: b'\n',
+        };
+        let mut buf = Vec::new();
+        write_event(
+            &mut buf,
+            &Event::Match(LineEvent::new(Path::new("a.txt"), &line, &re)),
+        )
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            concat!(
+                r#"{"type":"match","data":{"path":{"text":"a.txt"},"lines":{"text":"foo boo\n"},"#,
+                r#""line_number":2,"absolute_offset":8,"submatches":["#,
+                r#"{"match":{"text":"oo"},"start":1,"end":3},{"match":{"text":"oo"},"start":5,"end":7}]}}"#,
+                "\n"
+            )
+        );
+
+        // UTF-8이 아니면 base64로 인코딩한다.
+        let mut buf = Vec::new();
+        write_event(
+            &mut buf,
+            &Event::End {
+                path: Data::new(b"caf\xe9"),
+                stats: Stats {
+                    matched_lines: 1,
+                    bytes_searched: 10,
+                    elapsed: Duration::from_millis(1500).into(),
+                },
+            },
+        )
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            concat!(
+                r#"{"type":"end","data":{"path":{"bytes":"Y2Fm6Q=="},"stats":{"matched_lines":1,"#,
+                r#""bytes_searched":10,"elapsed":{"secs":1,"nanos":500000000,"human":"1.500000s"}}}}"#,
+                "\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,654 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+    ops::RangeInclusive,
+};
+
+use clap::ValueEnum;
+
+use super::matcher::Matcher;
+
+/// 바이너리 파일을 처리하는 방법(--binary-files)
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum BinaryFiles {
+    /// 매칭되면 "Binary file X matches"만 출력
+    #[default]
+    Binary,
+    /// 텍스트 파일처럼 처리
+    Text,
+    /// 매칭되지 않은 것으로 처리
+    WithoutMatch,
+}
+
+/// 처음 읽은 버퍼에 NUL 바이트가 있으면 바이너리 파일로 본다.
+/// 버퍼를 소비하지 않으므로 이후에 처음부터 다시 읽을 수 있다.
+pub fn is_binary(file: &mut impl BufRead) -> Result<bool, io::Error> {
+    Ok(file.fill_buf()?.contains(&0))
+}
+
+/// 앞뒤로 출력할 줄 수(-A, -B, -C)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Context {
+    pub before: usize,
+    pub after: usize,
+}
+
+/// 읽은 줄과 줄 번호, 파일의 처음부터 이 줄까지의 바이트 수
+/// UTF-8이 아닌 입력도 검색할 수 있도록 바이트열로 저장한다.
+/// -z일 때는 NUL 바이트로 끝나는 레코드를 한 줄로 다룬다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub number: u64,
+    pub offset: u64,
+    pub text: Vec<u8>,
+    /// 줄을 끝내는 바이트(`\n` 또는 -z일 때 `\0`)
+    pub terminator: u8,
+}
+
+impl Line {
+    /// 줄 끝의 구분 바이트를 뺀 내용이다.
+    /// `$` 같은 패턴이 줄 끝에서 매칭되도록 이 내용으로 검색한다.
+    pub fn content(&self) -> &[u8] {
+        self.text
+            .strip_suffix(&[self.terminator])
+            .unwrap_or(&self.text)
+    }
+
+    pub fn has_nul(&self) -> bool {
+        self.content().contains(&0)
+    }
+}
+
+/// `LineFinder`가 산출하는 항목
+#[derive(Debug, Clone, PartialEq)]
+pub enum FoundLine {
+    /// 선택된 줄
+    Match(Line),
+    /// 선택된 줄 앞뒤의 줄
+    Context(Line),
+    /// 이어지지 않는 묶음 사이의 구분자
+    Separator,
+}
+
+/// 줄을 선택하는 방법
+pub enum Selector<'a> {
+    /// 줄마다 패턴을 검사한다.
+    Pattern(&'a Matcher),
+    /// --multiline일 때 미리 찾아 둔 매칭이 걸친 줄 번호의 범위이다.
+    /// 범위는 오름차순이며 서로 겹치지 않는다.
+    Lines(Vec<RangeInclusive<u64>>),
+}
+
+impl Selector<'_> {
+    /// 줄 번호는 오름차순으로 주어져야 한다.
+    fn is_selected(&mut self, line: &Line) -> bool {
+        match self {
+            Selector::Pattern(pattern) => pattern.is_match(line.content()),
+            Selector::Lines(spans) => {
+                // 지나간 범위는 버린다.
+                let passed = spans
+                    .iter()
+                    .take_while(|span| *span.end() < line.number)
+                    .count();
+                spans.drain(..passed);
+                spans
+                    .first()
+                    .is_some_and(|span| span.contains(&line.number))
+            }
+        }
+    }
+}
+
+/// 파일 전체에서 찾은 매칭이 걸친 줄 번호의 범위를 구한다(--multiline).
+/// 매칭이 `\n`으로 끝나면 그 `\n`이 끝내는 줄까지만 포함한다.
+pub fn multiline_spans(text: &[u8], pattern: &Matcher, terminator: u8) -> Vec<RangeInclusive<u64>> {
+    let mut spans: Vec<RangeInclusive<u64>> = Vec::new();
+    // 앞에서부터 줄 수를 세면서 지나간다.
+    let (mut pos, mut number) = (0, 1);
+    let mut line_of = |target: usize| {
+        number += count_byte(&text[pos..target], terminator);
+        pos = target;
+        number
+    };
+
+    for range in pattern.find_iter(text) {
+        let start = line_of(range.start);
+        let end = if range.is_empty() {
+            start
+        } else {
+            line_of(range.end - 1)
+        };
+        match spans.last_mut() {
+            // 이어지거나 겹치는 범위는 합친다.
+            Some(last) if start <= *last.end() + 1 => *last = *last.start()..=end.max(*last.end()),
+            _ => spans.push(start..=end),
+        }
+    }
+
+    spans
+}
+
+fn count_byte(bytes: &[u8], byte: u8) -> u64 {
+    bytes.iter().filter(|&&b| b == byte).count() as u64
+}
+
+/// 한 줄씩 읽으면서 선택된 줄과 그 앞뒤의 줄을 산출하는 이터레이터이다.
+/// 앞의 줄은 링 버퍼에 `context.before`개까지만 저장하므로 파일 전체를 메모리에 올리지 않는다.
+pub struct LineFinder<'a, T: BufRead> {
+    file: T,
+    selector: Selector<'a>,
+    /// 줄을 끝내는 바이트
+    terminator: u8,
+    invert: bool,
+    /// `None`이면 앞뒤의 줄과 구분자를 산출하지 않는다.
+    context: Option<Context>,
+    /// 앞의 줄을 저장하는 링 버퍼
+    before_buf: VecDeque<Line>,
+    /// 아직 출력해야 하는 뒤의 줄 수
+    after_remaining: usize,
+    /// 마지막으로 산출한 줄 번호
+    last_number: Option<u64>,
+    number: u64,
+    offset: u64,
+    /// 선택할 최대 줄 수(-m)
+    max_count: Option<u64>,
+    /// 지금까지 선택한 줄 수
+    match_count: u64,
+    /// 한 번에 여러 항목을 산출할 때 사용한다.
+    queue: VecDeque<FoundLine>,
+}
+
+pub fn find_lines<T: BufRead>(
+    file: T,
+    selector: Selector<'_>,
+    terminator: u8,
+    invert: bool,
+    context: Option<Context>,
+    max_count: Option<u64>,
+) -> LineFinder<'_, T> {
+    LineFinder {
+        file,
+        selector,
+        terminator,
+        invert,
+        context,
+        before_buf: VecDeque::with_capacity(context.map_or(0, |c| c.before)),
+        after_remaining: 0,
+        last_number: None,
+        number: 0,
+        offset: 0,
+        max_count,
+        match_count: 0,
+        queue: VecDeque::new(),
+    }
+}
+
+impl<T: BufRead> LineFinder<'_, T> {
+    /// 지금까지 읽은 바이트 수
+    /// -m으로 멈추면 파일의 크기보다 작을 수 있다.
+    pub fn bytes_read(&self) -> u64 {
+        self.offset
+    }
+
+    /// 선택된 줄을 처리한다.
+    fn push_match(&mut self, line: Line) {
+        if let Some(context) = self.context {
+            // 앞의 줄을 포함한 묶음의 첫 줄이 마지막 줄과 이어지지 않으면 구분자를 넣는다.
+            let first_number = self.before_buf.front().map_or(line.number, |l| l.number);
+            if self
+                .last_number
+                .is_some_and(|last_number| first_number > last_number + 1)
+            {
+                self.queue.push_back(FoundLine::Separator);
+            }
+            self.queue
+                .extend(self.before_buf.drain(..).map(FoundLine::Context));
+            self.after_remaining = context.after;
+        }
+
+        self.last_number = Some(line.number);
+        self.match_count += 1;
+        self.queue.push_back(FoundLine::Match(line));
+    }
+
+    /// -m으로 지정한 줄 수를 모두 선택했는지 확인한다.
+    fn is_max_count_reached(&self) -> bool {
+        self.max_count
+            .is_some_and(|max_count| self.match_count >= max_count)
+    }
+
+    /// 선택되지 않은 줄을 처리한다.
+    fn push_other(&mut self, line: Line) {
+        let Some(context) = self.context else {
+            return;
+        };
+
+        if self.after_remaining > 0 {
+            self.after_remaining -= 1;
+            self.last_number = Some(line.number);
+            self.queue.push_back(FoundLine::Context(line));
+        } else if context.before > 0 {
+            if self.before_buf.len() == context.before {
+                self.before_buf.pop_front();
+            }
+            self.before_buf.push_back(line);
+        }
+    }
+}
+
+impl<T: BufRead> Iterator for LineFinder<'_, T> {
+    type Item = Result<FoundLine, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(found) = self.queue.pop_front() {
+                return Some(Ok(found));
+            }
+            // 최대 줄 수를 선택한 뒤에는 남은 뒤의 줄만 출력하고 끝낸다.
+            let is_max_count_reached = self.is_max_count_reached();
+            if is_max_count_reached && self.after_remaining == 0 {
+                return None;
+            }
+
+            let mut text = Vec::new();
+            match self.file.read_until(self.terminator, &mut text) {
+                Err(e) => return Some(Err(e)),
+                // EOF를 만나면 끝낸다.
+                Ok(0) => return None,
+                Ok(_) => {}
+            }
+            self.number += 1;
+
+            let line = Line {
+                number: self.number,
+                offset: self.offset,
+                text,
+                terminator: self.terminator,
+            };
+            self.offset += line.text.len() as u64;
+            // `true && false` || `false && true` 일 때
+            if !is_max_count_reached && self.selector.is_selected(&line) ^ self.invert {
+                self.push_match(line);
+            } else {
+                self.push_other(line);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use regex::bytes::{Regex, RegexBuilder};
+
+    use super::{Context, FoundLine, Line, Selector, find_lines, is_binary, multiline_spans};
+    use crate::args::matcher::Matcher;
+
+    /// 선택된 줄만 모은다.
+    fn find_matches(text: &[u8], pattern: &Regex, invert: bool) -> Vec<String> {
+        find_lines(
+            Cursor::new(text),
+            Selector::Pattern(&Matcher::Regex(pattern.clone())),
+            b'\n',
+            invert,
+            None,
+            None,
+        )
+        .map(|found| match found.unwrap() {
+            FoundLine::Match(line) => String::from_utf8_lossy(&line.text).into_owned(),
+            found => panic!("unexpected {found:?}"),
+        })
+        .collect()
+    }
+
+    fn to_strings(found: Vec<FoundLine>) -> Vec<String> {
+        found
+            .into_iter()
+            .map(|found| match found {
+                FoundLine::Match(Line { number, text, .. }) => {
+                    format!("{number}:{}", String::from_utf8_lossy(&text))
+                }
+                FoundLine::Context(Line { number, text, .. }) => {
+                    format!("{number}-{}", String::from_utf8_lossy(&text))
+                }
+                FoundLine::Separator => "--".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_lines() {
+        let text = b"Lorem\nIpsum\r\nDOLOR";
+
+        // 패턴 _or_은 "Lorem" 한 줄을 매칭한다.
+        let re1 = Regex::new("or").unwrap();
+        let matches = find_matches(text, &re1, false);
+        pretty_assertions::assert_eq!(matches, vec!["Lorem\n"]);
+
+        // 뒤집기가 설정되면 이 함수는 다른 두 줄을 매칭한다.
+        let matches = find_matches(text, &re1, true);
+        pretty_assertions::assert_eq!(matches, vec!["Ipsum\r\n", "DOLOR"]);
+
+        // 대소문자를 구문하지 않는 정규식이다.
+        let re2 = RegexBuilder::new("or")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+
+        // 두줄을 매칭해야 한다.
+        let matches = find_matches(text, &re2, false);
+        pretty_assertions::assert_eq!(matches, vec!["Lorem\n", "DOLOR"]);
+
+        // 뒤집기가 설정되면 남은 한줄은 매칭한다.
+        let matches = find_matches(text, &re2, true);
+        pretty_assertions::assert_eq!(matches, vec!["Ipsum\r\n"]);
+    }
+
+    #[test]
+    fn test_context() {
+        let text = b"a\nb\nmatch\nc\nd\ne\nf\nmatch\ng\nmatch\nh\n";
+        let re = Matcher::Regex(Regex::new("match").unwrap());
+
+        // 앞뒤로 한 줄씩 출력하고 이어지지 않는 묶음 사이에는 구분자를 넣는다.
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            Some(Context {
+                before: 1,
+                after: 1,
+            }),
+            None,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            to_strings(found),
+            vec![
+                "2-b\n",
+                "3:match\n",
+                "4-c\n",
+                "--",
+                "7-f\n",
+                "8:match\n",
+                "9-g\n",
+                "10:match\n",
+                "11-h\n"
+            ]
+        );
+
+        // 묶음이 이어지면 구분자를 넣지 않는다.
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            Some(Context {
+                before: 0,
+                after: 4,
+            }),
+            None,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            to_strings(found),
+            vec![
+                "3:match\n",
+                "4-c\n",
+                "5-d\n",
+                "6-e\n",
+                "7-f\n",
+                "8:match\n",
+                "9-g\n",
+                "10:match\n",
+                "11-h\n"
+            ]
+        );
+
+        // 앞의 줄은 파일의 처음을 넘지 않는다.
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            Some(Context {
+                before: 5,
+                after: 0,
+            }),
+            None,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            to_strings(found),
+            vec![
+                "1-a\n",
+                "2-b\n",
+                "3:match\n",
+                "4-c\n",
+                "5-d\n",
+                "6-e\n",
+                "7-f\n",
+                "8:match\n",
+                "9-g\n",
+                "10:match\n"
+            ]
+        );
+
+        // -A 0에서도 이어지지 않는 줄 사이에는 구분자를 넣는다.
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            Some(Context::default()),
+            None,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            to_strings(found),
+            vec!["3:match\n", "--", "8:match\n", "--", "10:match\n"]
+        );
+    }
+
+    #[test]
+    fn test_max_count() {
+        let text = b"a\nmatch\nb\nmatch\nc\nmatch\n";
+        let re = Matcher::Regex(Regex::new("match").unwrap());
+
+        // 최대 줄 수를 선택한 뒤에는 뒤의 줄만 출력한다.
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            Some(Context {
+                before: 0,
+                after: 2,
+            }),
+            Some(1),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(to_strings(found), vec!["2:match\n", "3-b\n", "4-match\n"]);
+
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            None,
+            Some(2),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(to_strings(found), vec!["2:match\n", "4:match\n"]);
+
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            None,
+            Some(0),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_offset() {
+        let text = b"ab\n\nmatch$\nmatch";
+        // `$`는 줄 끝의 `\n` 앞에서 매칭되어야 한다.
+        let re = Matcher::Regex(Regex::new("match$").unwrap());
+
+        let offsets = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            None,
+            None,
+        )
+        .map(|found| match found.unwrap() {
+            FoundLine::Match(line) => (line.number, line.offset),
+            found => panic!("unexpected {found:?}"),
+        })
+        .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(offsets, vec![(4, 11)]);
+    }
+
+    #[test]
+    fn test_bytes_read() {
+        let re = Matcher::Regex(Regex::new("a").unwrap());
+        let mut finds = find_lines(
+            Cursor::new(b"ab\ncd\nae\n"),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            None,
+            None,
+        );
+        assert!(finds.by_ref().all(|found| found.is_ok()));
+        pretty_assertions::assert_eq!(finds.bytes_read(), 9);
+
+        // -m으로 멈추면 나머지는 읽지 않는다.
+        let mut finds = find_lines(
+            Cursor::new(b"ab\ncd\nae\n"),
+            Selector::Pattern(&re),
+            b'\n',
+            false,
+            None,
+            Some(1),
+        );
+        assert!(finds.by_ref().all(|found| found.is_ok()));
+        pretty_assertions::assert_eq!(finds.bytes_read(), 3);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        // UTF-8이 아닌 바이트가 있어도 멈추지 않고 검색한다.
+        let text = b"caf\xe9 match\n\xff\xfe\nmatch\n";
+        let re = Regex::new("match").unwrap();
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&Matcher::Regex(re)),
+            b'\n',
+            false,
+            None,
+            None,
+        )
+        .map(|found| match found.unwrap() {
+            FoundLine::Match(line) => line.text,
+            found => panic!("unexpected {found:?}"),
+        })
+        .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(
+            found,
+            vec![b"caf\xe9 match\n".to_vec(), b"match\n".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_is_binary() {
+        let mut file = Cursor::new(b"text\0binary\n");
+        assert!(is_binary(&mut file).unwrap());
+        // 버퍼를 소비하지 않는다.
+        pretty_assertions::assert_eq!(file.position(), 0);
+
+        assert!(!is_binary(&mut Cursor::new(b"caf\xe9\n")).unwrap());
+        assert!(!is_binary(&mut Cursor::new(b"")).unwrap());
+    }
+
+    #[test]
+    fn test_null_data() {
+        // -z일 때는 NUL 바이트로 끝나는 레코드를 한 줄로 다룬다.
+        let text = b"one fox\0two\nlines fox\0three\0";
+        let re = Matcher::Regex(Regex::new("fox$").unwrap());
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Pattern(&re),
+            b'\0',
+            false,
+            None,
+            None,
+        )
+        .map(|found| match found.unwrap() {
+            FoundLine::Match(line) => (line.number, line.content().to_vec()),
+            found => panic!("unexpected {found:?}"),
+        })
+        .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(
+            found,
+            vec![(1, b"one fox".to_vec()), (2, b"two\nlines fox".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_multiline() {
+        let text = b"a\nstart\nmiddle\nend\nb\nstart end\nc\n";
+        let re = Matcher::Regex(
+            RegexBuilder::new("start.*?end\n?")
+                .dot_matches_new_line(true)
+                .build()
+                .unwrap(),
+        );
+        // 매칭이 끝내는 `\n`의 다음 줄은 포함하지 않는다.
+        let spans = multiline_spans(text, &re, b'\n');
+        pretty_assertions::assert_eq!(spans, vec![2..=4, 6..=6]);
+
+        let found = find_lines(
+            Cursor::new(&text),
+            Selector::Lines(spans),
+            b'\n',
+            false,
+            Some(Context {
+                before: 0,
+                after: 1,
+            }),
+            None,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            to_strings(found),
+            vec![
+                "2:start\n",
+                "3:middle\n",
+                "4:end\n",
+                "5-b\n",
+                "6:start end\n",
+                "7-c\n"
+            ]
+        );
+
+        // 이어지는 범위는 합친다.
+        let re = Matcher::Regex(Regex::new("(?s)a.b|c").unwrap());
+        pretty_assertions::assert_eq!(multiline_spans(b"a\nb\nc\n", &re, b'\n'), vec![1..=3]);
+    }
+}
@@ -0,0 +1,541 @@
+use std::ops::Range;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use regex::bytes::{Regex, RegexBuilder};
+
+/// 패턴을 해석하는 방법
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatcherOptions {
+    /// 대소문자 구분 없이 검색(-i)
+    pub insensitive: bool,
+    /// 패턴을 고정 문자열로 해석(-F)
+    pub fixed_strings: bool,
+    /// 단어 전체가 매칭될 때만 선택(-w)
+    pub word: bool,
+    /// 줄 전체가 매칭될 때만 선택(-x)
+    pub line: bool,
+    /// 여러 줄에 걸쳐 검색(--multiline)
+    /// `.`이 `\n`과 매칭되고 `^`, `$`는 각 줄의 처음과 끝에서 매칭된다.
+    pub multiline: bool,
+    /// 패턴을 Perl 정규식으로 해석(-P)
+    /// pcre 기능이 없으면 `Args`에서 먼저 오류를 반환한다.
+    #[cfg_attr(not(feature = "pcre"), allow(dead_code))]
+    pub perl: bool,
+}
+
+/// 여러 패턴 중 하나라도 매칭되는지 검사한다.
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    /// 패턴들을 하나의 정규식으로 합친다.
+    Regex(Regex),
+    /// -w일 때는 정규식이 찾은 매칭의 앞뒤를 고정 문자열과 같은 방법으로 검사한다.
+    Word(Regex),
+    /// 고정 문자열은 Aho-Corasick으로 한꺼번에 검색한다.
+    Fixed {
+        ac: AhoCorasick,
+        word: bool,
+        line: bool,
+        multiline: bool,
+    },
+    /// 전후방 탐색과 역참조를 지원하는 백트래킹 엔진이다.
+    /// 패턴을 합치면 캡처 그룹의 번호가 바뀌어서 역참조가 다른 그룹을 가리키므로 따로 컴파일한다.
+    #[cfg(feature = "pcre")]
+    Fancy {
+        res: Vec<fancy_regex::Regex>,
+        word: bool,
+    },
+}
+
+impl Matcher {
+    /// 패턴이 잘못되었으면 그 패턴을 오류로 반환한다.
+    pub fn new(patterns: &[String], options: MatcherOptions) -> Result<Matcher, String> {
+        #[cfg(feature = "pcre")]
+        if options.perl {
+            return Matcher::new_fancy(patterns, options);
+        }
+
+        // `AhoCorasick`의 대소문자 무시는 ASCII만 지원하므로 그 밖의 문자는 정규식으로 처리한다.
+        let use_fixed = options.fixed_strings
+            && !(options.insensitive && patterns.iter().any(|pattern| !pattern.is_ascii()));
+        if use_fixed {
+            let ac = AhoCorasickBuilder::new()
+                .ascii_case_insensitive(options.insensitive)
+                // -w, -x를 검사하려면 겹치는 매칭도 모두 찾아야 한다.
+                .match_kind(MatchKind::Standard)
+                .build(patterns)
+                .map_err(|_| patterns.join("\n"))?;
+            return Ok(Matcher::Fixed {
+                ac,
+                word: options.word,
+                line: options.line,
+                multiline: options.multiline,
+            });
+        }
+
+        // 패턴을 하나씩 검사해서 잘못된 패턴을 알려준다.
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let pattern = if options.fixed_strings {
+                    regex::escape(pattern)
+                } else {
+                    pattern.clone()
+                };
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(options.insensitive)
+                    .build()
+                    .map(|_| format!("(?:{pattern})"))
+                    .map_err(|_| pattern)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let joined = join_patterns(&patterns, options, r"\b{end-half}");
+        let re = RegexBuilder::new(&joined)
+            .case_insensitive(options.insensitive)
+            .multi_line(options.multiline)
+            .dot_matches_new_line(options.multiline)
+            .build()
+            .map_err(|_| joined)?;
+
+        // -x가 있으면 -w는 의미가 없다.
+        Ok(if options.word && !options.line {
+            Matcher::Word(re)
+        } else {
+            Matcher::Regex(re)
+        })
+    }
+
+    /// 패턴을 하나씩 `fancy_regex`로 컴파일한다.
+    #[cfg(feature = "pcre")]
+    fn new_fancy(patterns: &[String], options: MatcherOptions) -> Result<Matcher, String> {
+        let flags = match (options.insensitive, options.multiline) {
+            (true, true) => "(?ims)",
+            (true, false) => "(?i)",
+            (false, true) => "(?ms)",
+            (false, false) => "",
+        };
+        let res = patterns
+            .iter()
+            .map(|pattern| {
+                // 캡처하지 않는 그룹으로만 감싸므로 패턴 안의 역참조 번호는 그대로이다.
+                let wrapped = join_patterns(&[format!("(?:{pattern})")], options, r"(?!\w)");
+                fancy_regex::Regex::new(&format!("{flags}{wrapped}")).map_err(|_| pattern.clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Matcher::Fancy {
+            res,
+            word: options.word && !options.line,
+        })
+    }
+
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        match self {
+            Matcher::Regex(re) => re.is_match(haystack),
+            Matcher::Word(re) => {
+                find_word_at(haystack, 0, |h, start| regex_find_at(re, h, start)).is_some()
+            }
+            Matcher::Fixed { .. } => self.fixed_candidates(haystack).next().is_some(),
+            #[cfg(feature = "pcre")]
+            Matcher::Fancy { res, word: false } => fancy_find_at(res, haystack, 0).is_some(),
+            #[cfg(feature = "pcre")]
+            Matcher::Fancy { res, word: true } => {
+                find_word_at(haystack, 0, |h, start| fancy_find_at(res, h, start)).is_some()
+            }
+        }
+    }
+
+    /// 겹치지 않는 매칭의 위치를 앞에서부터 산출한다.
+    pub fn find_iter(&self, haystack: &[u8]) -> Vec<Range<usize>> {
+        match self {
+            Matcher::Regex(re) => re.find_iter(haystack).map(|m| m.range()).collect(),
+            Matcher::Word(re) => find_all(haystack, |start| {
+                find_word_at(haystack, start, |h, start| regex_find_at(re, h, start))
+            }),
+            #[cfg(feature = "pcre")]
+            Matcher::Fancy { res, word: false } => {
+                find_all(haystack, |start| fancy_find_at(res, haystack, start))
+            }
+            #[cfg(feature = "pcre")]
+            Matcher::Fancy { res, word: true } => find_all(haystack, |start| {
+                find_word_at(haystack, start, |h, start| fancy_find_at(res, h, start))
+            }),
+            Matcher::Fixed { .. } => {
+                // 가장 앞에서 시작하는 가장 긴 매칭을 고른다.
+                let mut candidates = self.fixed_candidates(haystack).collect::<Vec<_>>();
+                candidates.sort_by_key(|range| (range.start, usize::MAX - range.end));
+
+                let mut end = 0;
+                candidates
+                    .into_iter()
+                    .filter(|range| {
+                        if range.start < end {
+                            return false;
+                        }
+                        end = range.end;
+                        true
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// -w, -x 조건을 만족하는 고정 문자열의 매칭을 모두 산출한다.
+    fn fixed_candidates<'h>(
+        &'h self,
+        haystack: &'h [u8],
+    ) -> impl Iterator<Item = Range<usize>> + 'h {
+        let Matcher::Fixed {
+            ac,
+            word,
+            line,
+            multiline,
+        } = self
+        else {
+            unreachable!("Must be Matcher::Fixed");
+        };
+
+        ac.find_overlapping_iter(haystack)
+            .map(|m| m.range())
+            .filter(move |range| {
+                // --multiline일 때는 파일 전체를 검색하므로 줄의 경계를 확인한다.
+                if *line && *multiline {
+                    return (range.start == 0 || haystack[range.start - 1] == b'\n')
+                        && (range.end == haystack.len() || haystack[range.end] == b'\n');
+                }
+                if *line {
+                    return range.start == 0 && range.end == haystack.len();
+                }
+                !*word || is_word_bounded(haystack, range)
+            })
+    }
+}
+
+/// 여러 패턴을 하나로 합치고 -w, -x를 적용한다.
+/// `word_end`는 뒤에 단어 문자가 없음을 확인하는 엔진별 문법이다.
+fn join_patterns(patterns: &[String], options: MatcherOptions, word_end: &str) -> String {
+    // 패턴이 없으면(-f에 빈 파일) 아무것도 매칭하지 않는다.
+    let joined = if patterns.is_empty() {
+        r"[^\s\S]".to_string()
+    } else {
+        patterns.join("|")
+    };
+    if options.line {
+        format!("^(?:{joined})$")
+    } else if options.word {
+        // 앞뒤의 문자는 `find_word_at`에서 -F와 같은 방법으로 검사한다.
+        // 끝 쪽은 정규식 안에서도 확인해야 `foo|foobar`처럼 더 긴 대안을 고를 수 있다.
+        format!("(?:{joined}){word_end}")
+    } else {
+        joined
+    }
+}
+
+/// `start`부터 찾은 첫 매칭이다.
+fn regex_find_at(re: &Regex, haystack: &[u8], start: usize) -> Option<Range<usize>> {
+    re.find_at(haystack, start).map(|m| m.range())
+}
+
+/// `find_at`으로 겹치지 않는 매칭을 앞에서부터 모두 찾는다.
+fn find_all(haystack: &[u8], find_at: impl Fn(usize) -> Option<Range<usize>>) -> Vec<Range<usize>> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while start <= haystack.len() {
+        let Some(range) = find_at(start) else {
+            break;
+        };
+        // 빈 매칭이면 다음 문자부터 다시 찾는다.
+        start = if range.is_empty() {
+            range.end + char_len_at(haystack, range.end)
+        } else {
+            range.end
+        };
+        found.push(range);
+    }
+
+    found
+}
+
+/// -w일 때 `start`부터 단어 전체가 매칭되는 첫 매칭을 찾는다.
+/// GNU grep처럼 앞뒤가 맞지 않으면 같은 위치에서 더 짧은 매칭을 찾고,
+/// 그래도 없으면 다음 문자부터 다시 찾는다.
+fn find_word_at(
+    haystack: &[u8],
+    mut start: usize,
+    find_at: impl Fn(&[u8], usize) -> Option<Range<usize>>,
+) -> Option<Range<usize>> {
+    while start <= haystack.len() {
+        let first = find_at(haystack, start)?;
+        let mut range = first.clone();
+        loop {
+            if is_word_bounded(haystack, &range) {
+                return Some(range);
+            }
+            if range.is_empty() {
+                break;
+            }
+            // 끝을 잘라낸 바이트열에서도 같은 위치에서 시작하면 더 짧은 매칭이다.
+            match find_at(&haystack[..range.end - 1], range.start) {
+                Some(shorter) if shorter.start == range.start => range = shorter,
+                _ => break,
+            }
+        }
+        start = first.start + char_len_at(haystack, first.start);
+    }
+
+    None
+}
+
+/// GNU grep처럼 매칭의 바로 앞뒤가 단어 문자가 아니어야 한다(-w).
+fn is_word_bounded(haystack: &[u8], range: &Range<usize>) -> bool {
+    !char_before(&haystack[..range.start]).is_some_and(is_word_char)
+        && !char_after(&haystack[range.end..]).is_some_and(is_word_char)
+}
+
+/// `pos`에 있는 문자의 길이이다. UTF-8이 아니거나 끝이면 1이다.
+fn char_len_at(haystack: &[u8], pos: usize) -> usize {
+    char_after(&haystack[pos.min(haystack.len())..]).map_or(1, char::len_utf8)
+}
+
+/// `fancy_regex`는 `&str`만 검색하므로 UTF-8로 올바른 부분만 검색한다.
+/// 반환하는 위치는 `haystack` 전체에서의 바이트 위치이다.
+/// 합친 정규식처럼 가장 앞에서 시작하는 매칭을 고르고, 같은 위치이면 앞의 패턴을 고른다.
+#[cfg(feature = "pcre")]
+fn fancy_find_at(
+    res: &[fancy_regex::Regex],
+    haystack: &[u8],
+    start: usize,
+) -> Option<Range<usize>> {
+    let find = |valid: &str, pos: usize| {
+        res.iter()
+            // 백트래킹 한도를 넘는 등의 오류는 매칭되지 않은 것으로 본다.
+            .filter_map(|re| re.find_from_pos(valid, pos).ok().flatten())
+            .min_by_key(|m| m.start())
+            .map(|m| m.range())
+    };
+    // 빈 줄에는 조각이 없다.
+    if haystack.is_empty() {
+        return find("", 0);
+    }
+
+    let mut offset = 0;
+    for chunk in haystack.utf8_chunks() {
+        let valid = chunk.valid();
+        if start <= offset + valid.len()
+            && let Some(range) = find(valid, start.saturating_sub(offset))
+        {
+            return Some(offset + range.start..offset + range.end);
+        }
+        offset += valid.len() + chunk.invalid().len();
+    }
+
+    None
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 바이트열의 마지막 문자이다. UTF-8이 아니면 `None`이다.
+fn char_before(bytes: &[u8]) -> Option<char> {
+    (1..=bytes.len().min(4))
+        .find_map(|len| str::from_utf8(&bytes[bytes.len() - len..]).ok())
+        .and_then(|s| s.chars().next_back())
+}
+
+/// 바이트열의 첫 문자이다. UTF-8이 아니면 `None`이다.
+fn char_after(bytes: &[u8]) -> Option<char> {
+    (1..=bytes.len().min(4))
+        .find_map(|len| str::from_utf8(&bytes[..len]).ok())
+        .and_then(|s| s.chars().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matcher, MatcherOptions};
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_regex() {
+        let matcher =
+            Matcher::new(&patterns(&["fo+", "ba[rz]"]), MatcherOptions::default()).unwrap();
+        assert!(matcher.is_match(b"xfoo"));
+        assert!(matcher.is_match(b"baz"));
+        assert!(!matcher.is_match(b"bay"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"foo bar fo"), vec![0..3, 4..7, 8..10]);
+
+        // 잘못된 패턴을 알려준다.
+        let res = Matcher::new(&patterns(&["ok", "*foo"]), MatcherOptions::default());
+        pretty_assertions::assert_eq!(res.unwrap_err(), "*foo");
+
+        // 패턴이 없으면 아무것도 매칭하지 않는다.
+        let matcher = Matcher::new(&[], MatcherOptions::default()).unwrap();
+        assert!(!matcher.is_match(b""));
+        assert!(!matcher.is_match(b"foo"));
+    }
+
+    #[test]
+    fn test_word_line() {
+        let options = MatcherOptions {
+            word: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["the"]), options).unwrap();
+        assert!(matcher.is_match(b"in the house"));
+        assert!(!matcher.is_match(b"there"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"other the"), vec![6..9]);
+
+        // 패턴의 처음이나 끝이 단어 문자가 아니어도 -F와 같이 앞뒤의 문자만 검사한다.
+        for fixed_strings in [false, true] {
+            let options = MatcherOptions {
+                word: true,
+                fixed_strings,
+                ..Default::default()
+            };
+            let matcher = Matcher::new(&patterns(&["@foo", "bar."]), options).unwrap();
+            assert!(!matcher.is_match(b"ab@foo x"));
+            assert!(matcher.is_match(b"a @foo"));
+            assert!(matcher.is_match(b"bar. x"));
+            assert!(!matcher.is_match(b"bar.x"));
+        }
+
+        // 앞뒤가 맞지 않으면 같은 위치에서 더 짧은 매칭을 찾는다.
+        let matcher = Matcher::new(&patterns(&["foo.?"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"foo.x foo"), vec![0..3, 6..9]);
+        // 맞는 매칭이 없으면 다음 위치부터 다시 찾는다.
+        pretty_assertions::assert_eq!(matcher.find_iter(b"xfoo foo."), vec![5..9]);
+        // 먼저 나오는 대안이 맞지 않으면 더 긴 대안을 고른다.
+        let matcher = Matcher::new(&patterns(&["foo|foobar"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"foobar foo"), vec![0..6, 7..10]);
+
+        let options = MatcherOptions {
+            line: true,
+            insensitive: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["foo", "bar"]), options).unwrap();
+        assert!(matcher.is_match(b"FOO"));
+        assert!(!matcher.is_match(b"foo bar"));
+    }
+
+    #[test]
+    fn test_fixed() {
+        let options = MatcherOptions {
+            fixed_strings: true,
+            ..Default::default()
+        };
+        // 정규식의 특수 문자도 그대로 검색한다.
+        let matcher = Matcher::new(&patterns(&["a.c", "*"]), options).unwrap();
+        assert!(matches!(matcher, Matcher::Fixed { .. }));
+        assert!(matcher.is_match(b"xa.cx"));
+        assert!(!matcher.is_match(b"abc"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"*a.c*"), vec![0..1, 1..4, 4..5]);
+
+        // 가장 앞에서 시작하는 가장 긴 매칭을 고른다.
+        let matcher = Matcher::new(&patterns(&["ab", "abcd", "bc"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"abcdbc"), vec![0..4, 4..6]);
+
+        // -w는 단어 경계를 검사하고, 맞지 않으면 다른 매칭을 찾는다.
+        let options = MatcherOptions {
+            fixed_strings: true,
+            word: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["foo", "foo bar"]), options).unwrap();
+        assert!(matcher.is_match(b"a foo"));
+        assert!(!matcher.is_match(b"foobar"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"foo barx foo"), vec![0..3, 9..12]);
+        // 앞뒤의 문자가 여러 바이트이거나 UTF-8이 아니어도 검사한다.
+        assert!(!matcher.is_match("éfoo".as_bytes()));
+        assert!(matcher.is_match(b"\xfffoo\xe9"));
+
+        // -x는 줄 전체를 검사한다.
+        let options = MatcherOptions {
+            fixed_strings: true,
+            line: true,
+            insensitive: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["foo", "foo bar"]), options).unwrap();
+        assert!(matcher.is_match(b"FOO BAR"));
+        assert!(!matcher.is_match(b"foo ba"));
+
+        // ASCII가 아닌 패턴의 대소문자 무시는 정규식으로 처리한다.
+        let options = MatcherOptions {
+            fixed_strings: true,
+            insensitive: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["émile", "a+"]), options).unwrap();
+        assert!(matches!(matcher, Matcher::Regex(_)));
+        assert!(matcher.is_match("ÉMILE".as_bytes()));
+        assert!(matcher.is_match(b"A+"));
+        assert!(!matcher.is_match(b"aa"));
+    }
+
+    #[cfg(feature = "pcre")]
+    #[test]
+    fn test_perl() {
+        let options = MatcherOptions {
+            perl: true,
+            ..Default::default()
+        };
+        // 전방 탐색
+        let matcher = Matcher::new(&patterns(&[r"fox(?= jumps)"]), options).unwrap();
+        assert!(matcher.is_match(b"The fox jumps"));
+        assert!(!matcher.is_match(b"The fox sleeps"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"a fox jumps"), vec![2..5]);
+
+        // 역참조
+        let matcher = Matcher::new(&patterns(&[r"(\w)\1"]), options).unwrap();
+        assert!(matcher.is_match(b"bookkeeper"));
+        assert!(!matcher.is_match(b"abc"));
+
+        // 패턴마다 역참조 번호가 1부터 시작한다.
+        let matcher = Matcher::new(&patterns(&[r"(x)\1", r"(y)\1"]), options).unwrap();
+        assert!(matcher.is_match(b"yy"));
+        pretty_assertions::assert_eq!(matcher.find_iter(b"yy xx yy"), vec![0..2, 3..5, 6..8]);
+
+        // UTF-8이 아닌 바이트를 건너뛰고 위치는 원래 바이트열에서 계산한다.
+        let matcher = Matcher::new(&patterns(&[r"(?<=\s)fox"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"\xff fox\xfe fox"), vec![2..5, 7..10]);
+
+        let options = MatcherOptions {
+            perl: true,
+            insensitive: true,
+            word: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&[r"(?<!quick )fox"]), options).unwrap();
+        assert!(matcher.is_match(b"a FOX"));
+        assert!(!matcher.is_match(b"quick fox"));
+        assert!(!matcher.is_match(b"foxes"));
+
+        pretty_assertions::assert_eq!(
+            Matcher::new(&patterns(&["(?<=a)b", "("]), options).unwrap_err(),
+            "("
+        );
+    }
+
+    #[test]
+    fn test_multiline() {
+        let options = MatcherOptions {
+            multiline: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["^b.c$"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"a\nb\nc\nd"), vec![2..5]);
+
+        let options = MatcherOptions {
+            fixed_strings: true,
+            line: true,
+            multiline: true,
+            ..Default::default()
+        };
+        let matcher = Matcher::new(&patterns(&["foo"]), options).unwrap();
+        pretty_assertions::assert_eq!(matcher.find_iter(b"foo\nfoo bar\nfoo"), vec![0..3, 12..15]);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    io::{self, Write},
+    mem,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use super::printer::Printer;
+
+/// 차례가 된 파일도 이만큼 모아서 표준 출력에 쓴다.
+const CHUNK_SIZE: usize = 64 << 10;
+/// 차례가 오지 않은 파일이 버퍼에 담을 수 있는 크기
+/// 넘으면 차례가 올 때까지 검색을 멈추므로 메모리 사용량이 파일의 크기와 상관없다.
+const PENDING_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Default)]
+struct State {
+    /// 표준 출력에 쓸 차례인 파일의 인덱스
+    next: usize,
+    /// 앞뒤의 줄을 출력한 묶음이 있는지 여부
+    /// 다음 파일의 첫 묶음 앞에 구분자를 넣는다.
+    printed_group: bool,
+    /// 출력을 멈췄으면 기다리는 작업 스레드를 깨워서 끝낸다.
+    closed: bool,
+}
+
+/// 파일 순서대로 표준 출력에 쓰기 위해서 작업 스레드들이 공유한다.
+#[derive(Debug, Default)]
+pub struct Turn {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Turn {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 파일 하나를 모두 출력했으면 다음 파일의 차례이다.
+    pub fn advance(&self) {
+        self.lock().next += 1;
+        self.changed.notify_all();
+    }
+
+    /// 더 이상 출력하지 않는다.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.changed.notify_all();
+    }
+
+    /// `index`의 차례가 올 때까지 기다린다.
+    fn wait(&self, index: usize) -> Result<MutexGuard<'_, State>, io::Error> {
+        let mut state = self.lock();
+        loop {
+            if state.closed {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            if state.next == index {
+                return Ok(state);
+            }
+            state = self
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// 파일 하나의 출력
+/// 차례가 되면 표준 출력에 바로 쓰고, 차례가 오기 전에는 버퍼에 담는다.
+pub struct OrderedOutput<'a> {
+    index: usize,
+    turn: &'a Turn,
+    printer: &'a Printer<'a>,
+    buffer: Vec<u8>,
+    /// 버퍼에서 묶음이 시작하는 위치들
+    /// 차례가 되어야 앞의 파일이 묶음을 출력했는지 알 수 있으므로 구분자는 쓸 때 넣는다.
+    groups: Vec<usize>,
+    /// 표준 출력에 쓰다가 발생한 오류
+    /// 읽기 오류와 달리 회복할 수 없으므로 출력 순서를 정하는 쪽에 넘긴다.
+    error: Option<io::Error>,
+}
+
+impl<'a> OrderedOutput<'a> {
+    pub fn new(index: usize, turn: &'a Turn, printer: &'a Printer<'a>) -> OrderedOutput<'a> {
+        OrderedOutput {
+            index,
+            turn,
+            printer,
+            buffer: Vec::new(),
+            groups: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// 앞뒤의 줄을 출력하는 묶음을 시작한다.
+    /// 앞의 파일이나 아카이브 안의 앞 파일에서 출력한 묶음과의 사이에 구분자를 넣는다.
+    pub fn begin_group(&mut self) {
+        self.groups.push(self.buffer.len());
+    }
+
+    /// 버퍼를 표준 출력에 쓴다.
+    fn write_buffer(&mut self, state: &mut State) -> Result<(), io::Error> {
+        let mut stdout = io::stdout().lock();
+        let mut start = 0;
+        for group in mem::take(&mut self.groups) {
+            stdout.write_all(&self.buffer[start..group])?;
+            if state.printed_group {
+                self.printer.print_separator(&mut stdout)?;
+            }
+            state.printed_group = true;
+            start = group;
+        }
+        stdout.write_all(&self.buffer[start..])?;
+        stdout.flush()?;
+        self.buffer.clear();
+
+        Ok(())
+    }
+
+    /// 차례가 되었으면 버퍼를 비운다.
+    /// 버퍼가 너무 커지면 차례가 올 때까지 기다린다.
+    fn drain(&mut self, force: bool) -> Result<(), io::Error> {
+        let mut state = if self.buffer.len() >= PENDING_LIMIT || force {
+            self.turn.wait(self.index)?
+        } else {
+            let state = self.turn.lock();
+            if state.next != self.index {
+                return Ok(());
+            }
+            state
+        };
+
+        self.write_buffer(&mut state).inspect_err(|e| {
+            self.error = Some(io::Error::new(e.kind(), e.to_string()));
+        })
+    }
+
+    /// 검색을 마친 뒤에 남은 출력을 쓴다.
+    /// 차례가 아니면 버퍼를 남겨 두고, 차례가 된 뒤에 `force`로 다시 부른다.
+    pub fn finish(&mut self, force: bool) -> Result<(), io::Error> {
+        if let Some(e) = &self.error {
+            return Err(io::Error::new(e.kind(), e.to_string()));
+        }
+        self.drain(force)
+    }
+}
+
+impl Write for OrderedOutput<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_SIZE {
+            self.drain(false)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use super::{
+    colors::{self, Colors},
+    line_finder::{FoundLine, Line},
+    matcher::Matcher,
+};
+
+/// 찾은 줄을 출력하는 방법
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Printer<'a> {
+    /// 파일 이름 출력(-H, -h)
+    pub show_filename: bool,
+    /// 줄 번호 출력(-n)
+    pub line_number: bool,
+    /// 바이트 오프셋 출력(-b)
+    pub byte_offset: bool,
+    /// 매칭된 부분만 출력(-o)
+    pub only_matching: bool,
+    /// `None`이면 구분자를 출력하지 않는다.
+    pub group_separator: Option<&'a str>,
+    /// `None`이면 색을 사용하지 않는다.
+    pub colors: Option<&'a Colors>,
+    /// 줄 끝에 `\n` 대신 NUL 바이트를 출력(-z)
+    pub null_data: bool,
+}
+
+impl Printer<'_> {
+    /// 찾은 줄과 매칭된 부분을 끝낸다.
+    fn end_line(&self, writer: &mut impl Write) -> Result<(), io::Error> {
+        writer.write_all(if self.null_data { b"\0" } else { b"\n" })
+    }
+
+    /// 색을 사용할 때는 `color`가 고른 색으로 출력한다.
+    fn paint(
+        &self,
+        writer: &mut impl Write,
+        color: fn(&Colors) -> &str,
+        text: &[u8],
+    ) -> Result<(), io::Error> {
+        match self.colors {
+            Some(colors) => colors::paint(writer, color(colors), text),
+            None => writer.write_all(text),
+        }
+    }
+
+    fn paint_filename(&self, writer: &mut impl Write, filename: &Path) -> Result<(), io::Error> {
+        self.paint(
+            writer,
+            |c| &c.filename,
+            filename.as_os_str().as_encoded_bytes(),
+        )
+    }
+
+    fn print_sep(&self, writer: &mut impl Write, sep: char) -> Result<(), io::Error> {
+        self.paint(writer, |c| &c.separator, &[sep as u8])
+    }
+
+    /// "파일 이름:줄 번호:바이트 오프셋:"을 출력한다.
+    /// 선택된 줄은 `:`, 앞뒤의 줄은 `-`로 구분한다.
+    fn print_prefix(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+        number: u64,
+        offset: u64,
+        sep: char,
+    ) -> Result<(), io::Error> {
+        if self.show_filename {
+            self.paint_filename(writer, filename)?;
+            self.print_sep(writer, sep)?;
+        }
+        if self.line_number {
+            self.paint(writer, |c| &c.line_number, number.to_string().as_bytes())?;
+            self.print_sep(writer, sep)?;
+        }
+        if self.byte_offset {
+            self.paint(writer, |c| &c.byte_offset, offset.to_string().as_bytes())?;
+            self.print_sep(writer, sep)?;
+        }
+
+        Ok(())
+    }
+
+    fn print_text(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+        line: &Line,
+        sep: char,
+        pattern: &Matcher,
+    ) -> Result<(), io::Error> {
+        self.print_prefix(writer, filename, line.number, line.offset, sep)?;
+        let content = line.content();
+        match self.colors {
+            // 매칭된 부분에만 색을 입히고 나머지는 입력의 바이트를 그대로 출력한다.
+            // -v일 때는 앞뒤의 줄에서 매칭된 부분에 색을 입힌다.
+            Some(colors) => {
+                let mut end = 0;
+                for range in pattern.find_iter(content) {
+                    if range.is_empty() {
+                        continue;
+                    }
+                    writer.write_all(&content[end..range.start])?;
+                    colors::paint(writer, &colors.matched, &content[range.clone()])?;
+                    end = range.end;
+                }
+                writer.write_all(&content[end..])?;
+            }
+            None => writer.write_all(content)?,
+        }
+        // 마지막 줄에 줄바꿈이 없어도 줄바꿈을 출력한다.
+        self.end_line(writer)
+    }
+
+    /// `LineFinder`가 산출한 항목을 출력한다.
+    pub fn print_found(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+        found: &FoundLine,
+        pattern: &Matcher,
+    ) -> Result<(), io::Error> {
+        match found {
+            // -o일 때는 매칭된 부분을 한 줄씩 출력한다.
+            FoundLine::Match(line) if self.only_matching => pattern
+                .find_iter(line.content())
+                .into_iter()
+                // 빈 매칭은 출력하지 않는다.
+                .filter(|range| !range.is_empty())
+                .try_for_each(|range| {
+                    self.print_prefix(
+                        writer,
+                        filename,
+                        line.number,
+                        line.offset + range.start as u64,
+                        ':',
+                    )?;
+                    self.paint(writer, |c| &c.matched, &line.content()[range])?;
+                    self.end_line(writer)
+                }),
+            FoundLine::Match(line) => self.print_text(writer, filename, line, ':', pattern),
+            // -o일 때는 앞뒤의 줄을 출력하지 않는다.
+            FoundLine::Context(_) if self.only_matching => Ok(()),
+            FoundLine::Context(line) => self.print_text(writer, filename, line, '-', pattern),
+            FoundLine::Separator => self.print_separator(writer),
+        }
+    }
+
+    pub fn print_separator(&self, writer: &mut impl Write) -> Result<(), io::Error> {
+        match self.group_separator {
+            Some(sep) => {
+                self.paint(writer, |c| &c.separator, sep.as_bytes())?;
+                self.end_line(writer)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 바이너리 파일에서 매칭된 줄이 있으면 줄 대신 출력한다.
+    pub fn print_binary_matches(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+    ) -> Result<(), io::Error> {
+        write!(writer, "Binary file {} matches", filename.display())?;
+        self.end_line(writer)
+    }
+
+    /// -l, -L일 때 파일 이름을 출력한다.
+    pub fn print_filename(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+    ) -> Result<(), io::Error> {
+        self.paint_filename(writer, filename)?;
+        self.end_line(writer)
+    }
+
+    /// -c일 때 선택된 줄 수를 출력한다.
+    pub fn print_count(
+        &self,
+        writer: &mut impl Write,
+        filename: &Path,
+        count: u64,
+    ) -> Result<(), io::Error> {
+        if self.show_filename {
+            self.paint_filename(writer, filename)?;
+            self.print_sep(writer, ':')?;
+        }
+        write!(writer, "{count}")?;
+        self.end_line(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use regex::bytes::Regex;
+
+    use super::Printer;
+    use crate::args::{
+        line_finder::{FoundLine, Line},
+        matcher::Matcher,
+    };
+
+    fn print(printer: &Printer, found: &FoundLine, pattern: &Matcher) -> String {
+        let mut buf = Vec::new();
+        printer
+            .print_found(&mut buf, Path::new("a.txt"), found, pattern)
+            .unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_print_found() {
+        let re = Matcher::Regex(Regex::new("o.").unwrap());
+        let line = Line {
+            number: 3,
+            offset: 10,
+            text: b"foo boo\n".to_vec(),
+            terminator: b'\n',
+        };
+
+        let printer = Printer::default();
+        pretty_assertions::assert_eq!(
+            print(&printer, &FoundLine::Match(line.clone()), &re),
+            "foo boo\n"
+        );
+
+        let printer = Printer {
+            show_filename: true,
+            line_number: true,
+            byte_offset: true,
+            ..Default::default()
+        };
+        pretty_assertions::assert_eq!(
+            print(&printer, &FoundLine::Match(line.clone()), &re),
+            "a.txt:3:10:foo boo\n"
+        );
+        pretty_assertions::assert_eq!(
+            print(&printer, &FoundLine::Context(line.clone()), &re),
+            "a.txt-3-10-foo boo\n"
+        );
+
+        // -o일 때 오프셋은 매칭된 부분의 위치이다.
+        let printer = Printer {
+            byte_offset: true,
+            only_matching: true,
+            ..Default::default()
+        };
+        pretty_assertions::assert_eq!(
+            print(&printer, &FoundLine::Match(line.clone()), &re),
+            "11:oo\n15:oo\n"
+        );
+        pretty_assertions::assert_eq!(print(&printer, &FoundLine::Context(line), &re), "");
+    }
+}
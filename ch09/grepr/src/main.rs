@@ -1,4 +1,4 @@
-use grepr::args::Args;
+use grepr::args::{Args, ExitStatus};
 
 fn main() {
     // 빌더 패턴
@@ -6,8 +6,11 @@ fn main() {
     // 파생 패턴
     // let args = <Args as clap::Parser>::parse();
 
-    if let Err(e) = args.run() {
-        eprintln!("{}", e);
-        std::process::exit(1);
+    match args.run() {
+        Ok(status) => std::process::exit(status as i32),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(ExitStatus::Error as i32);
+        }
     }
 }
@@ -12,6 +12,13 @@ const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const NOBODY: &str = "tests/inputs/nobody.txt";
 const INPUTS_DIR: &str = "tests/inputs";
+const BINARY: &str = "tests/binary/binary.bin";
+const LATIN1: &str = "tests/binary/latin1.dat";
+const TREE_DIR: &str = "tests/tree";
+const RECORDS: &str = "tests/binary/records.dat";
+const TRACE: &str = "tests/multiline/trace.log";
+const ARCHIVE_DIR: &str = "tests/archive";
+const LOGS_TAR: &str = "tests/archive/logs.tar.gz";
 
 // --------------------------------------------------
 fn gen_bad_file() -> String {
@@ -63,6 +70,17 @@ fn warns_bad_file() -> Result<()> {
 
 // --------------------------------------------------
 fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    run_code(args, expected_file, 0)
+}
+
+// --------------------------------------------------
+/// 선택된 줄이 없으면 1로 종료한다.
+fn run_no_match(args: &[&str], expected_file: &str) -> Result<()> {
+    run_code(args, expected_file, 1)
+}
+
+// --------------------------------------------------
+fn run_code(args: &[&str], expected_file: &str, code: i32) -> Result<()> {
     let windows_file = format!("{expected_file}.windows");
     let expected_file = if os_type().unwrap() == "Windows" && Path::new(&windows_file).is_file() {
         &windows_file
@@ -72,17 +90,44 @@ fn run(args: &[&str], expected_file: &str) -> Result<()> {
 
     let expected = fs::read_to_string(expected_file)?;
     let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
-    assert!(output.status.success());
+    assert_eq!(output.status.code(), Some(code));
 
     let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
     assert_eq!(stdout, expected);
     Ok(())
 }
 
+// --------------------------------------------------
+/// UTF-8이 아닌 출력을 바이트 단위로 비교한다.
+fn run_bytes(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+/// 디렉터리를 읽는 순서는 파일 시스템마다 다르므로 정렬해서 비교한다.
+fn run_sorted(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
+    let mut actual_lines = stdout.lines().collect::<Vec<_>>();
+    let mut expected_lines = expected.lines().collect::<Vec<_>>();
+    actual_lines.sort();
+    expected_lines.sort();
+    assert_eq!(actual_lines, expected_lines);
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn empty_file() -> Result<()> {
-    run(&["foo", EMPTY], "tests/expected/foo.empty.txt")
+    run_no_match(&["foo", EMPTY], "tests/expected/foo.empty.txt")
 }
 
 // --------------------------------------------------
@@ -118,7 +163,7 @@ fn bustle_insensitive() -> Result<()> {
 // --------------------------------------------------
 #[test]
 fn nobody() -> Result<()> {
-    run(&["nobody", NOBODY], "tests/expected/nobody.txt")
+    run_no_match(&["nobody", NOBODY], "tests/expected/nobody.txt")
 }
 
 // --------------------------------------------------
@@ -196,7 +241,7 @@ fn insensitive_count() -> Result<()> {
 // --------------------------------------------------
 #[test]
 fn nobody_count() -> Result<()> {
-    run(&["-c", "nobody", NOBODY], "tests/expected/nobody.txt.count")
+    run_no_match(&["-c", "nobody", NOBODY], "tests/expected/nobody.txt.count")
 }
 
 // --------------------------------------------------
@@ -281,3 +326,869 @@ fn stdin_insensitive_count() -> Result<()> {
     assert_eq!(stdout, expected);
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn after_context() -> Result<()> {
+    run(
+        &["-A", "1", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.A1",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn before_context_insensitive() -> Result<()> {
+    run(
+        &["-B", "2", "-i", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.B2.insensitive",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn context_group_separator() -> Result<()> {
+    run(
+        &["-C", "1", "--group-separator=XX", "The", BUSTLE],
+        "tests/expected/bustle.txt.the.capitalized.C1.sepXX",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn context_no_group_separator() -> Result<()> {
+    run(
+        &["--context", "1", "--no-group-separator", "The", BUSTLE],
+        "tests/expected/bustle.txt.the.capitalized.C1.nosep",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn after_context_multiple_files() -> Result<()> {
+    run(
+        &["--after-context", "1", "-i", "to", NOBODY, BUSTLE, FOX],
+        "tests/expected/all.to.A1.insensitive",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn line_number_byte_offset_context() -> Result<()> {
+    run(
+        &["-nb", "-C", "1", "The", BUSTLE, FOX],
+        "tests/expected/all.the.capitalized.nb.C1",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn with_filename() -> Result<()> {
+    run(
+        &["-H", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.lowercase.H",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn no_filename() -> Result<()> {
+    run(
+        &["--no-filename", "-i", "the", BUSTLE, FOX],
+        "tests/expected/all.the.lowercase.insensitive.h",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn only_matching_byte_offset() -> Result<()> {
+    run(
+        &["-o", "--byte-offset", "-i", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.lowercase.insensitive.ob",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn files_with_matches() -> Result<()> {
+    run(
+        &["-l", "the", BUSTLE, EMPTY, FOX],
+        "tests/expected/all.the.lowercase.l",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn files_without_match() -> Result<()> {
+    run(
+        &["--files-without-match", "the", BUSTLE, EMPTY, FOX],
+        "tests/expected/all.the.lowercase.L",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn max_count_after_context() -> Result<()> {
+    run(
+        &["-m", "1", "-A", "2", "The", BUSTLE],
+        "tests/expected/bustle.txt.the.capitalized.m1.A2",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn multiple_patterns() -> Result<()> {
+    run(
+        &["-e", "The", "-e", "dog", BUSTLE, FOX],
+        "tests/expected/all.the.dog.e",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn pattern_file() -> Result<()> {
+    run(
+        &["-f", "tests/patterns.txt", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.patterns.f",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_pattern_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args(["-f", &bad, BUSTLE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fixed_strings_only_matching() -> Result<()> {
+    run(
+        &["-F", "-o", "you?", NOBODY],
+        "tests/expected/nobody.txt.you.F.o",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn word_regexp_insensitive() -> Result<()> {
+    run(
+        &["-w", "-i", "the", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.lowercase.insensitive.w",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn word_regexp_non_word_edges() -> Result<()> {
+    // 패턴의 처음이나 끝이 단어 문자가 아니어도 -F와 같이 매칭의 앞뒤 문자만 검사한다.
+    for fixed in [None, Some("-F")] {
+        for pattern in ["@foo", "x."] {
+            Command::cargo_bin(PRG)?
+                .args(fixed.iter().chain(&["-w", pattern]))
+                .write_stdin("ab@foo x.y\na @foo\nx. z\n")
+                .assert()
+                .success()
+                .stdout(if pattern == "@foo" {
+                    "a @foo\n"
+                } else {
+                    "x. z\n"
+                });
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn line_regexp() -> Result<()> {
+    run(
+        &["-x", "-e", "Until eternity.", "-e", "The", BUSTLE],
+        "tests/expected/bustle.txt.x",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn invert_fixed_strings_insensitive() -> Result<()> {
+    run(
+        &[
+            "-v", "-F", "-i", "-e", "the", "-e", ".", BUSTLE, EMPTY, FOX, NOBODY,
+        ],
+        "tests/expected/all.the.dot.vFi",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_file_matches() -> Result<()> {
+    // 바이너리 파일은 매칭된 줄 대신 메시지를 출력하고, UTF-8이 아닌 줄은 그대로 출력한다.
+    let mut expected = format!("Binary file {BINARY} matches\n{LATIN1}:caf").into_bytes();
+    expected.extend_from_slice(b"\xe9 fox\n");
+    Command::cargo_bin(PRG)?
+        .args(["fox", BINARY, LATIN1])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_file_count() -> Result<()> {
+    run(
+        &["-c", "fox", BINARY, LATIN1],
+        "tests/expected/binary.fox.count",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_file_text() -> Result<()> {
+    run_bytes(&["-a", "fox", BINARY], "tests/expected/binary.bin.fox.a")
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_files_without_match() -> Result<()> {
+    run(
+        &["-c", "--binary-files=without-match", "fox", BINARY, LATIN1],
+        "tests/expected/binary.fox.without-match.count",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn invalid_utf8() -> Result<()> {
+    run_bytes(&["-n", "fox", LATIN1], "tests/expected/latin1.dat.fox.n")
+}
+
+// --------------------------------------------------
+#[test]
+fn color_context() -> Result<()> {
+    run(
+        &["--color=always", "-n", "-C", "1", "The", BUSTLE, FOX],
+        "tests/expected/all.the.capitalized.n.C1.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_invert_context() -> Result<()> {
+    run(
+        &["--color=always", "-v", "-n", "-A", "1", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.lowercase.v.n.A1.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_only_matching() -> Result<()> {
+    run(
+        &["--color=always", "-H", "-ob", "the", BUSTLE],
+        "tests/expected/bustle.txt.the.lowercase.H.ob.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_count() -> Result<()> {
+    run(
+        &["--color=always", "-c", "the", BUSTLE, FOX],
+        "tests/expected/all.the.lowercase.count.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_files_with_matches() -> Result<()> {
+    run(
+        &["--color=always", "-l", "the", BUSTLE, EMPTY, FOX],
+        "tests/expected/all.the.lowercase.l.color",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn color_grep_colors() -> Result<()> {
+    let expected = fs::read_to_string("tests/expected/fox.txt.GREP_COLORS")?;
+    Command::cargo_bin(PRG)?
+        .env("GREP_COLORS", "ms=04;32:fn=01:ln=33:se=35")
+        .args(["--color=always", "-Hn", "fox", FOX])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn color_auto_not_terminal() -> Result<()> {
+    // 표준 출력이 터미널이 아니므로 색을 입히지 않는다.
+    run(
+        &["--color", "The", BUSTLE],
+        "tests/expected/bustle.txt.the.capitalized",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_tree() -> Result<()> {
+    run_sorted(
+        &["-r", "fox", TREE_DIR],
+        "tests/expected/tree.fox.recursive",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_include() -> Result<()> {
+    run_sorted(
+        &["-r", "--include=*.txt", "fox", TREE_DIR],
+        "tests/expected/tree.fox.include",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_exclude() -> Result<()> {
+    run_sorted(
+        &["-r", "--exclude=*.txt", "--exclude=.git*", "fox", TREE_DIR],
+        "tests/expected/tree.fox.exclude",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_exclude_dir() -> Result<()> {
+    run_sorted(
+        &[
+            "-r",
+            "--exclude-dir=skip",
+            "--exclude=ignored.txt",
+            "fox",
+            TREE_DIR,
+        ],
+        "tests/expected/tree.fox.exclude-dir",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn include_files() -> Result<()> {
+    run(
+        &[
+            "--include=*.md",
+            "fox",
+            "tests/tree/a.txt",
+            "tests/tree/b.md",
+        ],
+        "tests/expected/tree.fox.include.files",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_gitignore() -> Result<()> {
+    run_sorted(
+        &["-r", "--gitignore", "fox", TREE_DIR],
+        "tests/expected/tree.fox.gitignore",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_glob() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-r", "--include=a[b", "fox", TREE_DIR])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(r#"Invalid glob "a[b""#));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn threads_keep_file_order() -> Result<()> {
+    // 여러 스레드로 검색해도 파일 순서대로 출력하고 묶음 사이에 구분자를 넣는다.
+    for threads in ["1", "4"] {
+        run(
+            &["-j", threads, "-A", "1", "-i", "to", NOBODY, BUSTLE, FOX],
+            "tests/expected/all.to.A1.insensitive",
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn streams_before_input_ends() -> Result<()> {
+    use std::{
+        io::{Read, Write},
+        process::{self, Stdio},
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    let mut child = process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args(["-j", "4", "-h", "o", "-", FOX])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+
+    // 표준 입력을 닫지 않은 채로 출력이 나오는지 확인한다.
+    // 출력을 읽지 않으면 파이프가 차서 입력을 쓸 수 없으므로 다른 스레드에서 읽는다.
+    let line = "foo\n".repeat(1 << 16);
+    let len = line.len() / 2;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut first = vec![0; len];
+        let result = stdout.read_exact(&mut first).map(|_| (first, stdout));
+        tx.send(result).unwrap();
+    });
+    stdin.write_all(line.as_bytes())?;
+    let (first, mut stdout) = rx.recv_timeout(Duration::from_secs(10))??;
+    assert!(first.iter().all(|&b| b == b'\n' || b"fo".contains(&b)));
+
+    // 뒤의 파일은 앞의 파일이 끝난 뒤에 출력한다.
+    stdin.write_all(b"last foo\n")?;
+    drop(stdin);
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest)?;
+    assert!(child.wait()?.success());
+    assert!(rest.ends_with("last foo\nThe quick brown fox jumps over the lazy dog.\n"));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(feature = "pcre")]
+#[test]
+fn perl_regexp_lookbehind() -> Result<()> {
+    run(
+        &["-P", "-o", r"(?<=Nobody)\W+\w+", NOBODY],
+        "tests/expected/nobody.txt.P.lookbehind",
+    )
+}
+
+// --------------------------------------------------
+#[cfg(feature = "pcre")]
+#[test]
+fn perl_regexp_backreference() -> Result<()> {
+    run(
+        &["-P", "-i", r"(\w)\1", BUSTLE, NOBODY],
+        "tests/expected/all.P.backreference.insensitive",
+    )
+}
+
+// --------------------------------------------------
+#[cfg(feature = "pcre")]
+#[test]
+fn perl_regexp_backreference_per_pattern() -> Result<()> {
+    // 패턴마다 역참조 번호가 따로 매겨진다.
+    Command::cargo_bin(PRG)?
+        .args(["-P", "-e", r"(x)\1", "-e", r"(y)\1"])
+        .write_stdin("xx\nyy\nxy\n")
+        .assert()
+        .success()
+        .stdout("xx\nyy\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(not(feature = "pcre"))]
+#[test]
+fn dies_perl_regexp_without_feature() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-P", "fox", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--features pcre"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_perl_regexp_fixed_strings() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-P", "-F", "fox", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn null_data() -> Result<()> {
+    run_bytes(&["-z", "fox", RECORDS], "tests/expected/records.dat.fox.z")
+}
+
+// --------------------------------------------------
+#[test]
+fn null_data_context() -> Result<()> {
+    run_bytes(
+        &["-z", "-n", "-A", "1", "fox", RECORDS],
+        "tests/expected/records.dat.fox.z.n.A1",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn null_data_count() -> Result<()> {
+    // 줄 수와 파일 이름도 -z이면 NUL로 끝난다.
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-c", "fox", RECORDS, RECORDS])
+        .assert()
+        .success()
+        .stdout(format!("{RECORDS}:3\0{RECORDS}:3\0"));
+
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-l", "fox", RECORDS])
+        .assert()
+        .success()
+        .stdout(format!("{RECORDS}\0"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn null_data_only_matching() -> Result<()> {
+    run_bytes(
+        &["-z", "-o", "fox", RECORDS],
+        "tests/expected/records.dat.fox.z.o",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn multiline_stack_trace() -> Result<()> {
+    // 매칭이 걸친 줄을 모두 출력한다.
+    Command::cargo_bin(PRG)?
+        .args(["-U", "-n", r"^Traceback.*?^\w+Error: bad", TRACE])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "3:Traceback (most recent call last):\n",
+            "4:  File \"app.py\", line 10, in handle\n",
+            "5:    raise ValueError(\"bad input\")\n",
+            "6:ValueError: bad input\n",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn multiline_count_context() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-U", "-c", r"failed\nTraceback", TRACE])
+        .assert()
+        .success()
+        .stdout("4\n");
+
+    Command::cargo_bin(PRG)?
+        .args(["--multiline", "-B", "1", r"\)\n\w+Error", TRACE])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "  File \"app.py\", line 10, in handle\n",
+            "    raise ValueError(\"bad input\")\n",
+            "ValueError: bad input\n",
+            "--\n",
+            "  File \"app.py\", line 12, in handle\n",
+            "    raise KeyError(\"id\")\n",
+            "KeyError: 'id'\n",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn multiline_invert() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-U", "-v", r"(?s)Traceback.*?\n\w+Error[^\n]*\n", TRACE])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "INFO starting server\n",
+            "ERROR request failed\n",
+            "INFO retrying\n",
+            "ERROR request failed\n",
+            "INFO done\n",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_json(args: &[&str]) -> Result<Vec<serde_json::Value>> {
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    let mut events = Vec::new();
+    for line in String::from_utf8(output.stdout)?.lines() {
+        let mut event: serde_json::Value = serde_json::from_str(line)?;
+        // 걸린 시간은 실행할 때마다 다르다.
+        if let Some(data) = event["data"].as_object_mut() {
+            data.remove("elapsed_total");
+            data.remove("files");
+            if let Some(stats) = data.get_mut("stats").and_then(|s| s.as_object_mut()) {
+                stats.remove("elapsed");
+            }
+        }
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+// --------------------------------------------------
+#[test]
+fn json_events() -> Result<()> {
+    let events = run_json(&["--json", "-C", "1", "-i", "nobody", NOBODY, EMPTY])?;
+    let types = events
+        .iter()
+        .map(|event| event["type"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            "begin", "match", "match", "context", "end", "begin", "end", "summary"
+        ]
+    );
+
+    assert_eq!(
+        events[1],
+        serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": { "text": NOBODY },
+                "lines": { "text": "I'm Nobody! Who are you?\r\n" },
+                "line_number": 1,
+                "absolute_offset": 0,
+                "submatches": [
+                    { "match": { "text": "Nobody" }, "start": 4, "end": 10 },
+                ],
+            }
+        })
+    );
+    assert_eq!(events[3]["data"]["line_number"], 3);
+    assert_eq!(
+        events[4]["data"]["stats"],
+        serde_json::json!({ "matched_lines": 2, "bytes_searched": 248 })
+    );
+    assert_eq!(
+        events[7]["data"]["stats"],
+        serde_json::json!({
+            "searches": 2,
+            "searches_with_match": 1,
+            "matched_lines": 2,
+            "bytes_searched": 248,
+        })
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn json_submatches() -> Result<()> {
+    let events = run_json(&["--json", "o.", FOX])?;
+    assert_eq!(
+        events[1]["data"]["submatches"],
+        serde_json::json!([
+            { "match": { "text": "ow" }, "start": 12, "end": 14 },
+            { "match": { "text": "ox" }, "start": 17, "end": 19 },
+            { "match": { "text": "ov" }, "start": 26, "end": 28 },
+            { "match": { "text": "og" }, "start": 41, "end": 43 },
+        ])
+    );
+
+    // UTF-8이 아닌 줄은 base64로 인코딩한다.
+    let events = run_json(&["--json", "-a", "caf", LATIN1])?;
+    assert!(events[1]["data"]["lines"]["bytes"].is_string());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_json_count() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--json", "-c", "fox", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exit_codes() -> Result<()> {
+    Command::cargo_bin(PRG)?.args(["fox", FOX]).assert().code(0);
+    Command::cargo_bin(PRG)?
+        .args(["dog", EMPTY])
+        .assert()
+        .code(1);
+    // 선택된 줄이 있어도 오류가 있으면 2로 종료한다.
+    Command::cargo_bin(PRG)?
+        .args(["fox", FOX, &gen_bad_file()])
+        .assert()
+        .code(2);
+    Command::cargo_bin(PRG)?
+        .args(["*foo", FOX])
+        .assert()
+        .code(2);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-q", "fox", FOX, BUSTLE])
+        .assert()
+        .code(0)
+        .stdout("");
+    Command::cargo_bin(PRG)?
+        .args(["--quiet", "-c", "dog", EMPTY])
+        .assert()
+        .code(1)
+        .stdout("");
+    // -q일 때는 선택된 줄이 있으면 오류가 있어도 0으로 종료한다.
+    Command::cargo_bin(PRG)?
+        .args(["--silent", "fox", &gen_bad_file(), FOX])
+        .assert()
+        .code(0)
+        .stdout("");
+    Command::cargo_bin(PRG)?
+        .args(["-q", "dog", &gen_bad_file(), EMPTY])
+        .assert()
+        .code(2);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn no_messages() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "fox", &gen_bad_file(), INPUTS_DIR, FOX])
+        .assert()
+        .code(2)
+        .stdout(format!(
+            "{FOX}:The quick brown fox jumps over the lazy dog.\n"
+        ))
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive_bad_dir() -> Result<()> {
+    // 탐색하다 발생한 오류도 열 수 없는 파일처럼 알리고 2로 종료한다.
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .args(["-r", "fox", &bad])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::is_match(format!(
+            "^{bad}: .* \\(os error 2\\)\n$"
+        ))?);
+    Command::cargo_bin(PRG)?
+        .args(["-r", "-s", "fox", &bad, FOX])
+        .assert()
+        .code(2)
+        .stdout(format!(
+            "{FOX}:The quick brown fox jumps over the lazy dog.\n"
+        ))
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn search_zip_compressed() -> Result<()> {
+    for ext in ["gz", "xz", "bz2", "zst"] {
+        let file = format!("{ARCHIVE_DIR}/app.log.{ext}");
+        Command::cargo_bin(PRG)?
+            .args(["--search-zip", "-n", "ERROR", &file])
+            .assert()
+            .success()
+            .stdout("2:ERROR panic\n");
+    }
+
+    // --search-zip이 없으면 압축된 바이트를 그대로 검색한다.
+    Command::cargo_bin(PRG)?
+        .args(["ERROR", &format!("{ARCHIVE_DIR}/app.log.gz")])
+        .assert()
+        .code(1);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn search_zip_plain_magic() -> Result<()> {
+    // 매직 바이트로 시작하는 텍스트 파일은 그대로 검색한다.
+    Command::cargo_bin(PRG)?
+        .args(["--search-zip", "hello"])
+        .write_stdin("BZhello world\n")
+        .assert()
+        .success()
+        .stdout("BZhello world\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn search_zip_stdin() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--search-zip", "INFO"])
+        .pipe_stdin(format!("{ARCHIVE_DIR}/app.log.xz"))?
+        .assert()
+        .success()
+        .stdout("INFO boot\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn search_zip_tar() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--search-zip", "-n", "ERROR", LOGS_TAR])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{LOGS_TAR}:app/one.log:2:ERROR disk full\n\
+             {LOGS_TAR}:app/two.log:2:ERROR timeout\n\
+             {LOGS_TAR}:rotated.log:1:ERROR rotated\n"
+        ));
+
+    // 아카이브 안의 파일 사이에도 구분자를 넣는다.
+    Command::cargo_bin(PRG)?
+        .args(["--search-zip", "-h", "-A", "1", "ERROR", LOGS_TAR])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "ERROR disk full\n",
+            "INFO stop\n",
+            "--\n",
+            "ERROR timeout\n",
+            "--\n",
+            "ERROR rotated\n",
+            "INFO ok\n",
+        ));
+
+    Command::cargo_bin(PRG)?
+        .args(["--search-zip", "-l", "WARN", LOGS_TAR])
+        .assert()
+        .success()
+        .stdout(format!("{LOGS_TAR}:app/two.log\n"));
+    Ok(())
+}
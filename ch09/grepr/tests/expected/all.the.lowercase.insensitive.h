@@ -0,0 +1,4 @@
+The bustle in a house
+The morning after death
+The sweeping up the heart,
+The quick brown fox jumps over the lazy dog.
@@ -0,0 +1,5 @@
+tests/inputs/bustle.txt:The bustle in a house
+tests/inputs/bustle.txt:The morning after death
+tests/inputs/bustle.txt:The sweeping up the heart,
+tests/inputs/fox.txt:The quick brown fox jumps over the lazy dog.
+tests/inputs/nobody.txt:To tell one's name—the livelong June—
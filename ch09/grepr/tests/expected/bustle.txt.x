@@ -0,0 +1 @@
+Until eternity.
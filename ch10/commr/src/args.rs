@@ -1,9 +1,13 @@
+mod compare;
 mod line_iter;
 
-use std::io;
+use std::{io, num::NonZeroUsize};
 
-use clap::{Arg, ArgAction, Command, Parser};
-use line_iter::{LineIter, LinePosition};
+use clap::{Arg, ArgAction, Command, Parser, value_parser};
+use compare::{
+    ByteOrder, Collation, Compare, IgnoreCase, KeyField, NumericOrder, UnicodeOrder, VersionOrder,
+};
+use line_iter::{CheckOrder, LineIter, LinePosition};
 
 use crate::open;
 
@@ -25,9 +29,27 @@ pub struct Args {
     /// 3행을 표시하지 않음
     #[arg(short = '3', action(ArgAction::SetFalse))]
     show_col3: bool,
-    /// 대소문자를 구분하지 않음
-    #[arg(short, action(ArgAction::SetTrue))]
+    /// 4행(키는 같지만 내용이 다른 줄)을 표시하지 않음
+    #[arg(short = '4', action(ArgAction::SetFalse))]
+    show_col4: bool,
+    /// 줄 전체 대신 FIELD번째 필드로 비교
+    #[arg(short, long, value_name = "FIELD")]
+    key: Option<NonZeroUsize>,
+    /// 필드 구분 문자(기본값은 공백)
+    #[arg(short = 't', long, value_name = "SEP", requires("key"))]
+    field_separator: Option<char>,
+    /// 대소문자를 구분하지 않음(출력은 원래의 줄)
+    #[arg(short, long("ignore-case"), action(ArgAction::SetTrue))]
     insensitive: bool,
+    /// 앞부분의 숫자로 비교(sort -n으로 정렬한 입력)
+    #[arg(long, action(ArgAction::SetTrue), conflicts_with("version_sort"))]
+    numeric_sort: bool,
+    /// 문자열 속의 숫자를 수로 비교(sort -V로 정렬한 입력)
+    #[arg(long, action(ArgAction::SetTrue))]
+    version_sort: bool,
+    /// 문자열을 비교하는 방법
+    #[arg(long, value_name = "ORDER", default_value("byte"))]
+    collation: Collation,
     /// 행간 구분 문자
     #[arg(
         short,
@@ -36,6 +58,18 @@ pub struct Args {
         default_value("\t")
     )]
     delimiter: String,
+    /// 줄 구분자로 개행 대신 NUL을 사용
+    #[arg(short, long, action(ArgAction::SetTrue))]
+    zero_terminated: bool,
+    /// 마지막에 각 행의 줄 수를 출력
+    #[arg(long, action(ArgAction::SetTrue))]
+    total: bool,
+    /// 입력이 정렬되어 있는지 확인하고 아니면 멈춤
+    #[arg(long, action(ArgAction::SetTrue), overrides_with("nocheck_order"))]
+    check_order: bool,
+    /// 입력이 정렬되어 있는지 확인하지 않음
+    #[arg(long, action(ArgAction::SetTrue), overrides_with("check_order"))]
+    nocheck_order: bool,
     /// `derive_mode`를 활성화하는 디버그용 플래그
     #[arg(long("derive_mode"), hide(true), action(ArgAction::SetTrue))]
     derive_mode: bool,
@@ -77,12 +111,57 @@ impl Args {
                     .help("3행을 표시하지 않음")
                     .action(ArgAction::SetFalse),
             )
+            .arg(
+                Arg::new("show_col4")
+                    .short('4')
+                    .help("4행(키는 같지만 내용이 다른 줄)을 표시하지 않음")
+                    .action(ArgAction::SetFalse),
+            )
+            .arg(
+                Arg::new("key")
+                    .short('k')
+                    .long("key")
+                    .value_name("FIELD")
+                    .help("줄 전체 대신 FIELD번째 필드로 비교")
+                    .value_parser(value_parser!(NonZeroUsize)),
+            )
+            .arg(
+                Arg::new("field_separator")
+                    .short('t')
+                    .long("field-separator")
+                    .value_name("SEP")
+                    .help("필드 구분 문자(기본값은 공백)")
+                    .requires("key")
+                    .value_parser(value_parser!(char)),
+            )
             .arg(
                 Arg::new("insensitive")
                     .short('i')
-                    .help("대소문자를 구분하지 않음")
+                    .long("ignore-case")
+                    .help("대소문자를 구분하지 않음(출력은 원래의 줄)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("numeric_sort")
+                    .long("numeric-sort")
+                    .help("앞부분의 숫자로 비교(sort -n으로 정렬한 입력)")
+                    .conflicts_with("version_sort")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("version_sort")
+                    .long("version-sort")
+                    .help("문자열 속의 숫자를 수로 비교(sort -V로 정렬한 입력)")
                     .action(ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("collation")
+                    .long("collation")
+                    .value_name("ORDER")
+                    .help("문자열을 비교하는 방법")
+                    .default_value("byte")
+                    .value_parser(value_parser!(Collation)),
+            )
             .arg(
                 Arg::new("delimiter")
                     .short('d')
@@ -91,6 +170,33 @@ impl Args {
                     .help("행간 구분 문자")
                     .default_value("\t"),
             )
+            .arg(
+                Arg::new("zero_terminated")
+                    .short('z')
+                    .long("zero-terminated")
+                    .help("줄 구분자로 개행 대신 NUL을 사용")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("total")
+                    .long("total")
+                    .help("마지막에 각 행의 줄 수를 출력")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("check_order")
+                    .long("check-order")
+                    .help("입력이 정렬되어 있는지 확인하고 아니면 멈춤")
+                    .overrides_with("nocheck_order")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("nocheck_order")
+                    .long("nocheck-order")
+                    .help("입력이 정렬되어 있는지 확인하지 않음")
+                    .overrides_with("check_order")
+                    .action(ArgAction::SetTrue),
+            )
             .arg(
                 Arg::new("derive_mode")
                     .long("derive_mode")
@@ -108,13 +214,62 @@ impl Args {
             show_col1: matches.get_flag("show_col1"),
             show_col2: matches.get_flag("show_col2"),
             show_col3: matches.get_flag("show_col3"),
+            show_col4: matches.get_flag("show_col4"),
+            key: matches.get_one("key").copied(),
+            field_separator: matches.get_one("field_separator").copied(),
             insensitive: matches.get_flag("insensitive"),
+            numeric_sort: matches.get_flag("numeric_sort"),
+            version_sort: matches.get_flag("version_sort"),
+            // `.default_value("byte")`이므로 `unwrap`을 사용할 수 있다.
+            collation: matches.get_one("collation").copied().unwrap(),
             // `.default_value("\t")`이므로 `unwrap`을 사용할 수 있다.
             delimiter: matches.get_one("delimiter").cloned().unwrap(),
+            zero_terminated: matches.get_flag("zero_terminated"),
+            total: matches.get_flag("total"),
+            check_order: matches.get_flag("check_order"),
+            nocheck_order: matches.get_flag("nocheck_order"),
             derive_mode: matches.get_flag("derive_mode"),
         }
     }
 
+    /// 둘 다 없으면 짝이 없는 줄이 나온 뒤에만 확인한다.
+    fn check_order(&self) -> CheckOrder {
+        if self.check_order {
+            CheckOrder::Check
+        } else if self.nocheck_order {
+            CheckOrder::NoCheck
+        } else {
+            CheckOrder::Default
+        }
+    }
+
+    /// 입력을 정렬한 방법에 맞는 비교 함수를 고른다.
+    fn comparator(&self) -> Result<Box<dyn Compare>, anyhow::Error> {
+        let unicode = self.collation == Collation::Unicode;
+        let mut comparator: Box<dyn Compare> = if self.numeric_sort {
+            Box::new(NumericOrder)
+        } else if self.version_sort {
+            Box::new(VersionOrder)
+        } else if unicode {
+            // 유니코드 정렬 알고리즘은 대소문자를 구분하지 않는 비교를 지원한다.
+            Box::new(UnicodeOrder::new(self.insensitive)?)
+        } else {
+            Box::new(ByteOrder)
+        };
+        if self.insensitive && (self.numeric_sort || self.version_sort || !unicode) {
+            comparator = Box::new(IgnoreCase(comparator));
+        }
+        if let Some(field) = self.key {
+            comparator = Box::new(KeyField {
+                field,
+                separator: self.field_separator,
+                inner: comparator,
+            });
+        }
+
+        Ok(comparator)
+    }
+
     pub fn run(&self) -> Result<(), anyhow::Error> {
         let file1 = self.file1.as_ref();
         let file2 = self.file2.as_ref();
@@ -129,7 +284,15 @@ impl Args {
         let fh1 = get_bufread(file1)?;
         let fh2 = get_bufread(file2)?;
 
-        let line_iter = LineIter::new(fh1, fh2, self.insensitive);
+        let terminator = if self.zero_terminated { '\0' } else { '\n' };
+        let mut line_iter = LineIter::new(
+            fh1,
+            fh2,
+            self.comparator()?,
+            self.check_order(),
+            terminator as u8,
+            self.key.is_some(),
+        );
 
         let delim1 = if self.show_col1 {
             self.delimiter.as_str()
@@ -141,22 +304,39 @@ impl Args {
         } else {
             ""
         };
+        let delim3 = if self.show_col3 {
+            self.delimiter.as_str()
+        } else {
+            ""
+        };
 
-        line_iter.into_iter().try_for_each(|line_result| {
+        // 숨긴 행도 --total의 줄 수에는 포함한다.
+        let mut counts = [0usize; 4];
+        line_iter.by_ref().try_for_each(|line_result| {
             match line_result? {
                 LinePosition::First(st) => {
+                    counts[0] += 1;
                     if self.show_col1 {
-                        println!("{st}");
+                        print!("{st}{terminator}");
                     }
                 }
                 LinePosition::Second(st) => {
+                    counts[1] += 1;
                     if self.show_col2 {
-                        println!("{delim1}{st}");
+                        print!("{delim1}{st}{terminator}");
                     }
                 }
                 LinePosition::Both(st) => {
+                    counts[2] += 1;
                     if self.show_col3 {
-                        println!("{delim1}{delim2}{st}");
+                        print!("{delim1}{delim2}{st}{terminator}");
+                    }
+                }
+                LinePosition::Changed(st1, st2) => {
+                    counts[3] += 1;
+                    if self.show_col4 {
+                        let delim = &self.delimiter;
+                        print!("{delim1}{delim2}{delim3}{st1}{delim}{st2}{terminator}");
                     }
                 }
             };
@@ -164,6 +344,22 @@ impl Args {
             Result::<(), io::Error>::Ok(())
         })?;
 
+        if self.total {
+            let delim = &self.delimiter;
+            let [col1, col2, col3, col4] = counts;
+            print!("{col1}{delim}{col2}{delim}{col3}{delim}");
+            // 4행은 -k를 사용할 때만 있다.
+            if self.key.is_some() {
+                print!("{col4}{delim}");
+            }
+            print!("total{terminator}");
+        }
+
+        // 경고만 하고 끝까지 출력했더라도 실패로 끝낸다.
+        if line_iter.is_unsorted() {
+            anyhow::bail!("input is not in sorted order");
+        }
+
         Ok(())
     }
 }
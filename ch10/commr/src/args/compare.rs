@@ -0,0 +1,274 @@
+use std::{borrow::Cow, cmp::Ordering, num::NonZeroUsize};
+
+use clap::ValueEnum;
+use icu_collator::{Collator, CollatorOptions, Strength};
+
+/// `LineIter`가 두 줄의 순서를 정할 때 사용한다.
+/// 입력 파일을 정렬할 때 사용한 `sort`의 옵션과 같은 순서여야 한다.
+pub trait Compare {
+    /// 줄을 읽을 때 한 번만 계산하는 비교용 키이다.
+    /// `compare`에는 줄이 아니라 이 키를 전달한다.
+    fn key<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(line)
+    }
+
+    fn compare(&self, key1: &str, key2: &str) -> Ordering;
+}
+
+/// 문자열을 비교하는 방법(--collation)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Collation {
+    /// 바이트 순서(LC_ALL=C sort)
+    #[default]
+    Byte,
+    /// 유니코드 정렬 알고리즘(CLDR 기본 순서)
+    Unicode,
+}
+
+/// 바이트 순서로 비교한다.
+pub struct ByteOrder;
+
+impl Compare for ByteOrder {
+    fn compare(&self, line1: &str, line2: &str) -> Ordering {
+        line1.cmp(line2)
+    }
+}
+
+/// 대소문자를 구분하지 않고 비교한다(sort -f).
+/// `sort -f`처럼 대문자로 바꾼 키를 비교하므로 대소문자만 다른 줄은 같은 줄이다.
+/// 소문자로 바꾸면 `_`처럼 대문자와 소문자 사이에 있는 문자의 순서가 달라진다.
+pub struct IgnoreCase(pub Box<dyn Compare>);
+
+impl Compare for IgnoreCase {
+    fn key<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        Cow::Owned(self.0.key(line).to_ascii_uppercase())
+    }
+
+    fn compare(&self, key1: &str, key2: &str) -> Ordering {
+        self.0.compare(key1, key2)
+    }
+}
+
+/// 줄 전체가 아니라 필드 하나를 키로 삼는다(-k, -t).
+/// 구분자가 없으면 공백으로 필드를 나누고, 필드가 없으면 빈 문자열이다.
+pub struct KeyField {
+    /// 1부터 시작하는 필드 번호
+    pub field: NonZeroUsize,
+    pub separator: Option<char>,
+    pub inner: Box<dyn Compare>,
+}
+
+impl Compare for KeyField {
+    fn key<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let index = self.field.get() - 1;
+        let field = match self.separator {
+            Some(separator) => line.split(separator).nth(index),
+            None => line.split_whitespace().nth(index),
+        };
+        self.inner.key(field.unwrap_or_default())
+    }
+
+    fn compare(&self, key1: &str, key2: &str) -> Ordering {
+        self.inner.compare(key1, key2)
+    }
+}
+
+/// 앞부분의 숫자로 비교한다(sort -n).
+/// 숫자가 같으면 `sort`처럼 바이트 순서로 비교한다.
+pub struct NumericOrder;
+
+impl Compare for NumericOrder {
+    fn compare(&self, line1: &str, line2: &str) -> Ordering {
+        numeric_cmp(line1, line2).then_with(|| line1.cmp(line2))
+    }
+}
+
+/// 문자열 속의 숫자를 수로 비교한다(sort -V).
+pub struct VersionOrder;
+
+impl Compare for VersionOrder {
+    fn compare(&self, line1: &str, line2: &str) -> Ordering {
+        version_cmp(line1.as_bytes(), line2.as_bytes()).then_with(|| line1.cmp(line2))
+    }
+}
+
+/// 유니코드 정렬 알고리즘으로 비교한다.
+pub struct UnicodeOrder {
+    collator: Collator,
+    /// 대소문자를 구분하지 않을 때는 정렬 순서가 같으면 같은 줄이다.
+    insensitive: bool,
+}
+
+impl UnicodeOrder {
+    pub fn new(insensitive: bool) -> Result<UnicodeOrder, anyhow::Error> {
+        let mut options = CollatorOptions::new();
+        // 2단계까지는 악센트, 3단계부터 대소문자를 구분한다.
+        options.strength = Some(if insensitive {
+            Strength::Secondary
+        } else {
+            Strength::Tertiary
+        });
+        let collator = Collator::try_new(&Default::default(), options)
+            .map_err(|e| anyhow::anyhow!("Cannot load collation data: {e}"))?;
+
+        Ok(UnicodeOrder {
+            collator,
+            insensitive,
+        })
+    }
+}
+
+impl Compare for UnicodeOrder {
+    fn compare(&self, line1: &str, line2: &str) -> Ordering {
+        let order = self.collator.compare(line1, line2);
+        if self.insensitive {
+            order
+        } else {
+            order.then_with(|| line1.cmp(line2))
+        }
+    }
+}
+
+/// 앞의 공백을 건너뛰고 부호, 정수부, 소수부를 나눈다.
+/// 숫자가 없으면 0이다.
+fn parse_number(line: &str) -> (bool, &str, &str) {
+    let line = line.trim_start();
+    let (negative, line) = match line.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (int, rest) = line.split_at(digits(line));
+    let frac = rest
+        .strip_prefix('.')
+        .map_or("", |rest| &rest[..digits(rest)]);
+
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    // -0은 0과 같다.
+    let negative = negative && !(int.is_empty() && frac.is_empty());
+
+    (negative, int, frac)
+}
+
+/// 아주 큰 수도 비교할 수 있도록 문자열 그대로 비교한다.
+fn numeric_cmp(line1: &str, line2: &str) -> Ordering {
+    let (neg1, int1, frac1) = parse_number(line1);
+    let (neg2, int2, frac2) = parse_number(line2);
+    let magnitude = |int1: &str, frac1: &str, int2: &str, frac2: &str| {
+        int1.len()
+            .cmp(&int2.len())
+            .then_with(|| int1.cmp(int2))
+            .then_with(|| frac1.cmp(frac2))
+    };
+
+    match (neg1, neg2) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => magnitude(int1, frac1, int2, frac2),
+        (true, true) => magnitude(int2, frac2, int1, frac1),
+    }
+}
+
+/// GNU `sort -V`(filevercmp)의 비교 규칙이다.
+/// 숫자가 아닌 부분은 글자가 다른 기호보다 앞서고 `~`는 무엇보다도 앞선다.
+/// 숫자 부분은 앞의 0을 무시하고 수로 비교한다.
+fn version_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    fn order(c: Option<&u8>) -> i32 {
+        match c {
+            None => 0,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => *c as i32,
+            Some(b'~') => -1,
+            Some(c) => *c as i32 + 256,
+        }
+    }
+    let is_digit = |s: &[u8], i: usize| s.get(i).is_some_and(u8::is_ascii_digit);
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !is_digit(a, i)) || (j < b.len() && !is_digit(b, j)) {
+            let (c1, c2) = (order(a.get(i)), order(b.get(j)));
+            if c1 != c2 {
+                return c1.cmp(&c2);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        // 자릿수가 같으면 처음으로 다른 숫자가 순서를 정한다.
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if is_digit(a, i) {
+            return Ordering::Greater;
+        }
+        if is_digit(b, j) {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{ByteOrder, Compare, IgnoreCase, numeric_cmp, parse_number, version_cmp};
+
+    #[test]
+    fn test_ignore_case() {
+        let compare = IgnoreCase(Box::new(ByteOrder));
+        let cmp =
+            |line1: &str, line2: &str| compare.compare(&compare.key(line1), &compare.key(line2));
+
+        pretty_assertions::assert_eq!(compare.key("aB_c"), "AB_C");
+        pretty_assertions::assert_eq!(cmp("abc", "ABC"), Ordering::Equal);
+        // `sort -f`처럼 `_`는 글자보다 뒤에 온다.
+        pretty_assertions::assert_eq!(cmp("_x", "b"), Ordering::Greater);
+        pretty_assertions::assert_eq!(cmp("_x", "B"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        pretty_assertions::assert_eq!(parse_number("  -012.340x"), (true, "12", "34"));
+        pretty_assertions::assert_eq!(parse_number("-0.00"), (false, "", ""));
+        pretty_assertions::assert_eq!(parse_number(".5"), (false, "", "5"));
+        pretty_assertions::assert_eq!(parse_number("abc"), (false, "", ""));
+    }
+
+    #[test]
+    fn test_numeric_cmp() {
+        pretty_assertions::assert_eq!(numeric_cmp("10", "9"), Ordering::Greater);
+        pretty_assertions::assert_eq!(numeric_cmp(" -3", "-20"), Ordering::Greater);
+        pretty_assertions::assert_eq!(numeric_cmp("1.50", "1.5"), Ordering::Equal);
+        pretty_assertions::assert_eq!(numeric_cmp("-0", "abc"), Ordering::Equal);
+        pretty_assertions::assert_eq!(
+            numeric_cmp("123456789012345678901234567890", "99"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_version_cmp() {
+        pretty_assertions::assert_eq!(version_cmp(b"file10", b"file9"), Ordering::Greater);
+        pretty_assertions::assert_eq!(version_cmp(b"v1.01", b"v1.1"), Ordering::Equal);
+        pretty_assertions::assert_eq!(version_cmp(b"1.0~beta", b"1.0"), Ordering::Less);
+        pretty_assertions::assert_eq!(version_cmp(b"1.0a", b"1.0-"), Ordering::Less);
+    }
+}
@@ -1,50 +1,177 @@
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     io::{self, BufRead},
 };
 
+use super::compare::Compare;
+
 #[derive(PartialEq, Eq)]
 pub enum LinePosition {
     First(String),
     Second(String),
     Both(String),
+    /// 키는 같지만 내용이 다른 줄(첫 번째 파일의 줄, 두 번째 파일의 줄)
+    Changed(String, String),
+}
+
+/// 입력이 정렬되어 있는지 확인하는 방법
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckOrder {
+    /// 짝이 없는 줄이 나온 뒤에만 확인하고 경고한다.
+    #[default]
+    Default,
+    /// 항상 확인하고 정렬되지 않은 줄을 만나면 멈춘다(--check-order).
+    Check,
+    /// 확인하지 않는다(--nocheck-order).
+    NoCheck,
+}
+
+/// 읽은 줄과 비교에 사용하는 키
+struct Line {
+    text: String,
+    /// 줄과 키가 같으면 `None`이다.
+    key: Option<String>,
+}
+
+impl Line {
+    fn new(text: String, comparator: &dyn Compare) -> Line {
+        let key = match comparator.key(&text) {
+            // 줄의 일부를 빌린 키는 따로 저장해야 한다.
+            Cow::Borrowed(key) if key.len() == text.len() => None,
+            key => Some(key.into_owned()),
+        };
+        Line { text, key }
+    }
+
+    fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.text)
+    }
+}
+
+/// 입력 파일 하나의 상태
+struct Input {
+    lines: Box<dyn Iterator<Item = Result<String, io::Error>>>,
+    next: Option<Line>,
+    /// 순서를 확인하기 위해 저장하는 바로 앞의 줄의 키
+    prev: Option<String>,
+    /// 정렬되지 않은 줄을 발견했는지 여부
+    unsorted: bool,
+}
+
+impl Input {
+    fn new(fh: Box<dyn BufRead>, terminator: u8) -> Input {
+        let lines: Box<dyn Iterator<Item = Result<String, io::Error>>> = if terminator == b'\n' {
+            Box::new(fh.lines())
+        } else {
+            Box::new(fh.split(terminator).map(|line| {
+                String::from_utf8(line?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }))
+        };
+
+        Input {
+            lines: Box::new(lines.fuse()),
+            next: None,
+            prev: None,
+            unsorted: false,
+        }
+    }
+
+    /// 다음번 줄이 없으면 읽어서 키와 함께 저장한다.
+    /// 새로 읽었으면 `true`를 반환한다.
+    fn fill_next(&mut self, comparator: &dyn Compare) -> Result<bool, io::Error> {
+        if self.next.is_some() {
+            return Ok(false);
+        }
+        self.next = self
+            .lines
+            .next()
+            .transpose()?
+            .map(|text| Line::new(text, comparator));
+
+        Ok(true)
+    }
 }
 
 pub struct LineIter {
-    line1_iter: Box<dyn Iterator<Item = Result<String, io::Error>>>,
-    line2_iter: Box<dyn Iterator<Item = Result<String, io::Error>>>,
-    line1_next: Option<String>,
-    line2_next: Option<String>,
-    insensitive: bool,
+    input1: Input,
+    input2: Input,
+    comparator: Box<dyn Compare>,
+    check_order: CheckOrder,
+    /// 키가 같아도 내용이 다르면 `Changed`로 구분할지 여부
+    report_changed: bool,
+    /// 한쪽 파일에만 있는 줄을 산출했는지 여부
+    seen_unpairable: bool,
 }
 
 impl LineIter {
-    pub fn new(fh1: Box<dyn BufRead>, fh2: Box<dyn BufRead>, insensitive: bool) -> LineIter {
+    pub fn new(
+        fh1: Box<dyn BufRead>,
+        fh2: Box<dyn BufRead>,
+        comparator: Box<dyn Compare>,
+        check_order: CheckOrder,
+        terminator: u8,
+        report_changed: bool,
+    ) -> LineIter {
         LineIter {
-            line1_iter: Box::new(fh1.lines().fuse()),
-            line2_iter: Box::new(fh2.lines().fuse()),
-            line1_next: None,
-            line2_next: None,
-            insensitive,
+            input1: Input::new(fh1, terminator),
+            input2: Input::new(fh2, terminator),
+            comparator,
+            check_order,
+            report_changed,
+            seen_unpairable: false,
         }
     }
 
+    /// 정렬되지 않은 파일이 있었는지 여부
+    pub fn is_unsorted(&self) -> bool {
+        self.input1.unsorted || self.input2.unsorted
+    }
+
     /// 각 파일의 다음번 줄을 저장한다.
     fn fill_next(&mut self) -> Result<(), io::Error> {
-        let case_change = |s: String| {
-            if self.insensitive {
-                s.to_lowercase()
-            } else {
-                s
-            }
+        if self.input1.fill_next(self.comparator.as_ref())? {
+            self.check_order(1)?;
+        }
+        if self.input2.fill_next(self.comparator.as_ref())? {
+            self.check_order(2)?;
+        }
+
+        Ok(())
+    }
+
+    /// 새로 읽은 줄이 앞의 줄보다 앞서는지 확인한다.
+    /// 파일마다 한 번만 알리며, --check-order일 때는 오류를 반환한다.
+    fn check_order(&mut self, file: u8) -> Result<(), io::Error> {
+        let check = match self.check_order {
+            CheckOrder::Default => self.seen_unpairable,
+            CheckOrder::Check => true,
+            CheckOrder::NoCheck => false,
+        };
+        let input = if file == 1 {
+            &mut self.input1
+        } else {
+            &mut self.input2
+        };
+        let Some(next) = input.next.as_ref().map(Line::key) else {
+            return Ok(());
         };
 
-        if self.line1_next.is_none() {
-            self.line1_next = self.line1_iter.next().transpose()?.map(case_change);
-        }
-        if self.line2_next.is_none() {
-            self.line2_next = self.line2_iter.next().transpose()?.map(case_change);
+        if check
+            && !input.unsorted
+            && input
+                .prev
+                .as_ref()
+                .is_some_and(|prev| self.comparator.compare(prev, next) == Ordering::Greater)
+        {
+            input.unsorted = true;
+            let message = format!("file {file} is not in sorted order");
+            if self.check_order == CheckOrder::Check {
+                return Err(io::Error::other(message));
+            }
+            eprintln!("{message}");
         }
+        input.prev = Some(next.to_string());
 
         Ok(())
     }
@@ -59,21 +186,31 @@ impl Iterator for LineIter {
         }
 
         // 정말 편리한 `match`
-        match (&self.line1_next, &self.line2_next) {
+        let position = match (&self.input1.next, &self.input2.next) {
             // 아래는 `Some`인 것을 확인하므로 `unwrap`을 사용해도 안전하다
-            (None, None) => None,
-            (Some(line1_str), Some(line2_str)) => match line1_str.cmp(line2_str) {
-                Ordering::Equal => {
-                    self.line1_next = None;
-                    Some(Ok(LinePosition::Both(self.line2_next.take().unwrap())))
+            (None, None) => return None,
+            (Some(line1), Some(line2)) => match self.comparator.compare(line1.key(), line2.key()) {
+                Ordering::Equal if self.report_changed && line1.text != line2.text => {
+                    LinePosition::Changed(
+                        self.input1.next.take().unwrap().text,
+                        self.input2.next.take().unwrap().text,
+                    )
                 }
-                Ordering::Less => Some(Ok(LinePosition::First(self.line1_next.take().unwrap()))),
-                Ordering::Greater => {
-                    Some(Ok(LinePosition::Second(self.line2_next.take().unwrap())))
+                // 키가 같으면 첫 번째 파일의 줄을 그대로 출력한다.
+                Ordering::Equal => {
+                    self.input2.next = None;
+                    LinePosition::Both(self.input1.next.take().unwrap().text)
                 }
+                Ordering::Less => LinePosition::First(self.input1.next.take().unwrap().text),
+                Ordering::Greater => LinePosition::Second(self.input2.next.take().unwrap().text),
             },
-            (Some(_), None) => Some(Ok(LinePosition::First(self.line1_next.take().unwrap()))),
-            (None, Some(_)) => Some(Ok(LinePosition::Second(self.line2_next.take().unwrap()))),
+            (Some(_), None) => LinePosition::First(self.input1.next.take().unwrap().text),
+            (None, Some(_)) => LinePosition::Second(self.input2.next.take().unwrap().text),
+        };
+        if matches!(position, LinePosition::First(_) | LinePosition::Second(_)) {
+            self.seen_unpairable = true;
         }
+
+        Some(Ok(position))
     }
 }
@@ -10,6 +10,11 @@ const EMPTY: &str = "tests/inputs/empty.txt";
 const FILE1: &str = "tests/inputs/file1.txt";
 const FILE2: &str = "tests/inputs/file2.txt";
 const BLANK: &str = "tests/inputs/blank.txt";
+const UNSORTED: &str = "tests/inputs/unsorted.txt";
+const ZERO1: &str = "tests/inputs/zero1.txt";
+const ZERO2: &str = "tests/inputs/zero2.txt";
+const SNAP1: &str = "tests/inputs/snap1.csv";
+const SNAP2: &str = "tests/inputs/snap2.csv";
 
 // --------------------------------------------------
 #[test]
@@ -338,3 +343,232 @@ fn file1_file2_123_delim() -> Result<()> {
 fn blank_file1() -> Result<()> {
     run(&[BLANK, FILE1], "tests/expected/blank_file1.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn unsorted_file1() -> Result<()> {
+    // 경고를 출력하고 끝까지 비교한 뒤에 실패한다.
+    Command::cargo_bin(PRG)?
+        .args([UNSORTED, FILE1])
+        .assert()
+        .failure()
+        .stdout(fs::read_to_string("tests/expected/unsorted_file1.out")?)
+        .stderr("file 1 is not in sorted order\ninput is not in sorted order\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_file1_check_order() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--check-order", UNSORTED, FILE1])
+        .assert()
+        .failure()
+        .stdout(fs::read_to_string(
+            "tests/expected/unsorted_file1.check.out",
+        )?)
+        .stderr("file 1 is not in sorted order\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_file1_nocheck_order() -> Result<()> {
+    run(
+        &["--nocheck-order", UNSORTED, FILE1],
+        "tests/expected/unsorted_file1.nocheck.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_unsorted() -> Result<()> {
+    // 모든 줄이 짝을 이루면 기본값으로는 확인하지 않는다.
+    run(
+        &[UNSORTED, UNSORTED],
+        "tests/expected/unsorted_unsorted.default.out",
+    )?;
+
+    Command::cargo_bin(PRG)?
+        .args(["--check-order", UNSORTED, UNSORTED])
+        .assert()
+        .failure()
+        .stdout(fs::read_to_string("tests/expected/unsorted_unsorted.out")?)
+        .stderr("file 1 is not in sorted order\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers1_numbers2_numeric_sort() -> Result<()> {
+    // `sort -n`으로 정렬한 입력
+    run(
+        &[
+            "--numeric-sort",
+            "tests/inputs/numbers1.txt",
+            "tests/inputs/numbers2.txt",
+        ],
+        "tests/expected/numbers1_numbers2.n.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn versions1_versions2_version_sort() -> Result<()> {
+    // `sort -V`로 정렬한 입력
+    run(
+        &[
+            "--version-sort",
+            "tests/inputs/versions1.txt",
+            "tests/inputs/versions2.txt",
+        ],
+        "tests/expected/versions1_versions2.v.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn mixed1_mixed2_ignore_case() -> Result<()> {
+    // 두 파일에 모두 있는 줄은 첫 번째 파일의 대소문자 그대로 출력한다.
+    run(
+        &[
+            "--ignore-case",
+            "tests/inputs/mixed1.txt",
+            "tests/inputs/mixed2.txt",
+        ],
+        "tests/expected/mixed1_mixed2.i.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn underscore1_underscore2_ignore_case() -> Result<()> {
+    // `sort -f`는 대문자로 바꿔서 비교하므로 `_`가 글자보다 뒤에 온다.
+    Command::cargo_bin(PRG)?
+        .args([
+            "--check-order",
+            "-i",
+            "tests/inputs/underscore1.txt",
+            "tests/inputs/underscore2.txt",
+        ])
+        .assert()
+        .success()
+        .stdout("\ta\n\t\tb\n\t\t_x\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn words1_words2_collation_unicode() -> Result<()> {
+    run(
+        &[
+            "--collation",
+            "unicode",
+            "tests/inputs/words1.txt",
+            "tests/inputs/words2.txt",
+        ],
+        "tests/expected/words1_words2.unicode.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_numeric_and_version_sort() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--numeric-sort", "--version-sort", FILE1, FILE2])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_total() -> Result<()> {
+    run(
+        &[FILE1, FILE2, "--total"],
+        "tests/expected/file1_file2.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_12_total_delim() -> Result<()> {
+    // 숨긴 행의 줄 수도 출력한다.
+    run(
+        &[FILE1, FILE2, "-12", "--total", "-d", "::"],
+        "tests/expected/file1_file2.12.total.delim.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn zero1_zero2_z() -> Result<()> {
+    // 줄 안의 개행은 일반 문자이다.
+    run(&["-z", ZERO1, ZERO2], "tests/expected/zero1_zero2.z.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn zero1_zero2_z_total() -> Result<()> {
+    run(
+        &["--zero-terminated", "--total", ZERO1, ZERO2],
+        "tests/expected/zero1_zero2.z.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn snap1_snap2_key() -> Result<()> {
+    // 키는 같지만 내용이 다른 줄은 4행에 두 줄을 모두 출력한다.
+    run(
+        &["-k", "1", "-t", ",", SNAP1, SNAP2],
+        "tests/expected/snap1_snap2.k1.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn snap1_snap2_key_total() -> Result<()> {
+    run(
+        &[
+            "--key",
+            "1",
+            "--field-separator",
+            ",",
+            "--total",
+            SNAP1,
+            SNAP2,
+        ],
+        "tests/expected/snap1_snap2.k1.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn snap1_snap2_key_3_delim() -> Result<()> {
+    run(
+        &["-k1", "-t,", "-3", "-d", "|", SNAP1, SNAP2],
+        "tests/expected/snap1_snap2.k1.3.delim.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn snap1_snap2_key_4() -> Result<()> {
+    run(
+        &["-k1", "-t,", "-4", SNAP1, SNAP2],
+        "tests/expected/snap1_snap2.k1.4.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_separator_without_key() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-t", ",", SNAP1, SNAP2])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--key <FIELD>"));
+    Ok(())
+}
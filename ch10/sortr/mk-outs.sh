@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+
+set -u
+
+IN_DIR="tests/inputs"
+OUT_DIR="tests/expected"
+
+[[ ! -d "$OUT_DIR" ]] && mkdir -p "$OUT_DIR"
+rm -f $OUT_DIR/*
+
+# 바이트 순서로 비교한다.
+export LC_ALL=C
+
+WORDS="$IN_DIR/words.txt"
+for OPT in "" r u f fu fs b; do
+    sort ${OPT:+-$OPT} "$WORDS" >"$OUT_DIR/words.txt${OPT:+.$OPT}.out"
+done
+
+NUMBERS="$IN_DIR/numbers.txt"
+for OPT in n nr nu; do
+    sort -$OPT "$NUMBERS" >"$OUT_DIR/numbers.txt.$OPT.out"
+done
+
+sort -h "$IN_DIR/sizes.txt" >"$OUT_DIR/sizes.txt.h.out"
+sort -hr "$IN_DIR/sizes.txt" >"$OUT_DIR/sizes.txt.hr.out"
+sort -V "$IN_DIR/versions.txt" >"$OUT_DIR/versions.txt.V.out"
+
+TABLE="$IN_DIR/table.csv"
+sort -t , -k 2,2 "$TABLE" >"$OUT_DIR/table.csv.k2.out"
+sort -t , -k 2,2 -s "$TABLE" >"$OUT_DIR/table.csv.k2.s.out"
+sort -t , -k 2,2 -u "$TABLE" >"$OUT_DIR/table.csv.k2.u.out"
+sort -t , -k 2,2 -k 3,3nr "$TABLE" >"$OUT_DIR/table.csv.k2.k3nr.out"
+sort -t , -k 1.2,1.3 "$TABLE" >"$OUT_DIR/table.csv.k1.2.out"
+
+FIELDS="$IN_DIR/fields.txt"
+sort -k 2,2 "$FIELDS" >"$OUT_DIR/fields.txt.k2.out"
+sort -k 2b,2 "$FIELDS" >"$OUT_DIR/fields.txt.k2b.out"
+sort -k 2,2n -k 1,1r "$FIELDS" >"$OUT_DIR/fields.txt.k2n.k1r.out"
+
+sort "$WORDS" "$NUMBERS" >"$OUT_DIR/words.txt.numbers.txt.out"
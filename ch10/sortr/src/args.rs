@@ -0,0 +1,392 @@
+mod compare;
+mod key;
+mod merge;
+
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::{self, BufRead, BufWriter, Write},
+};
+
+use clap::{Arg, ArgAction, Command, Parser, value_parser};
+use compare::Comparator;
+use key::{KeyOptions, KeySpec, Order};
+use merge::ExternalSorter;
+
+use crate::open;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    author,
+    disable_help_flag = true,
+    disable_version_flag = true
+)]
+pub struct Args {
+    /// 입력 파일
+    #[arg(value_name = "FILE", default_value = "-", num_args(1..))]
+    files: Vec<String>,
+    /// 앞부분의 숫자로 비교
+    #[arg(
+        short,
+        long,
+        action(ArgAction::SetTrue),
+        conflicts_with_all(["human_numeric_sort", "version_sort"])
+    )]
+    numeric_sort: bool,
+    /// 2K, 1G처럼 단위가 붙은 숫자로 비교
+    #[arg(
+        short = 'h',
+        long,
+        action(ArgAction::SetTrue),
+        conflicts_with("version_sort")
+    )]
+    human_numeric_sort: bool,
+    /// 문자열 속의 숫자를 수로 비교
+    #[arg(short = 'V', long, action(ArgAction::SetTrue))]
+    version_sort: bool,
+    /// 대소문자를 구분하지 않음
+    #[arg(short = 'f', long, action(ArgAction::SetTrue))]
+    ignore_case: bool,
+    /// 앞의 공백을 무시
+    #[arg(short = 'b', long, action(ArgAction::SetTrue))]
+    ignore_leading_blanks: bool,
+    /// 역순으로 정렬
+    #[arg(short, long, action(ArgAction::SetTrue))]
+    reverse: bool,
+    /// 키가 같은 줄은 처음 것만 출력
+    #[arg(short, long, action(ArgAction::SetTrue))]
+    unique: bool,
+    /// 키가 같은 줄은 입력 순서를 유지
+    #[arg(short, long, action(ArgAction::SetTrue))]
+    stable: bool,
+    /// 정렬 키(POS1[,POS2], POS는 F[.C][OPTS])
+    #[arg(short, long, value_name = "KEYDEF")]
+    key: Vec<KeySpec>,
+    /// 필드 구분 문자
+    #[arg(short = 't', long, value_name = "SEP")]
+    field_separator: Option<char>,
+    /// 표준 출력 대신 FILE에 출력
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
+    /// 정렬되어 있는지 확인만 함
+    #[arg(short, long, action(ArgAction::SetTrue))]
+    check: bool,
+    /// 메모리에서 정렬할 크기(기본 단위는 K), 넘으면 임시 파일을 사용
+    #[arg(
+        short = 'S',
+        long,
+        value_name = "SIZE",
+        default_value = "64M",
+        value_parser = parse_size
+    )]
+    buffer_size: usize,
+    /// 도움말 출력(-h는 --human-numeric-sort이다.)
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+    /// 버전 출력(-V는 --version-sort이다.)
+    #[arg(long, action = ArgAction::Version)]
+    version: Option<bool>,
+    /// `derive_mode`를 활성화하는 디버그용 플래그
+    #[arg(long("derive_mode"), hide(true), action(ArgAction::SetTrue))]
+    derive_mode: bool,
+}
+
+impl Args {
+    pub fn parse() -> Args {
+        let matches = Command::new("sortr")
+            .version("0.1.0")
+            .author("TestAquatic")
+            .about("`sort`의 간단한 러스트 버전")
+            // -h는 --human-numeric-sort, -V는 --version-sort로 사용한다.
+            .disable_help_flag(true)
+            .disable_version_flag(true)
+            .arg(
+                Arg::new("files")
+                    .value_name("FILE")
+                    .help("입력 파일")
+                    .default_value("-")
+                    .num_args(1..),
+            )
+            .arg(
+                Arg::new("numeric_sort")
+                    .short('n')
+                    .long("numeric-sort")
+                    .help("앞부분의 숫자로 비교")
+                    .conflicts_with_all(["human_numeric_sort", "version_sort"])
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("human_numeric_sort")
+                    .short('h')
+                    .long("human-numeric-sort")
+                    .help("2K, 1G처럼 단위가 붙은 숫자로 비교")
+                    .conflicts_with("version_sort")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("version_sort")
+                    .short('V')
+                    .long("version-sort")
+                    .help("문자열 속의 숫자를 수로 비교")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("ignore_case")
+                    .short('f')
+                    .long("ignore-case")
+                    .help("대소문자를 구분하지 않음")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("ignore_leading_blanks")
+                    .short('b')
+                    .long("ignore-leading-blanks")
+                    .help("앞의 공백을 무시")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("reverse")
+                    .short('r')
+                    .long("reverse")
+                    .help("역순으로 정렬")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("unique")
+                    .short('u')
+                    .long("unique")
+                    .help("키가 같은 줄은 처음 것만 출력")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("stable")
+                    .short('s')
+                    .long("stable")
+                    .help("키가 같은 줄은 입력 순서를 유지")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("key")
+                    .short('k')
+                    .long("key")
+                    .value_name("KEYDEF")
+                    .help("정렬 키(POS1[,POS2], POS는 F[.C][OPTS])")
+                    .value_parser(value_parser!(KeySpec))
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("field_separator")
+                    .short('t')
+                    .long("field-separator")
+                    .value_name("SEP")
+                    .help("필드 구분 문자")
+                    .value_parser(value_parser!(char)),
+            )
+            .arg(
+                Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .value_name("FILE")
+                    .help("표준 출력 대신 FILE에 출력"),
+            )
+            .arg(
+                Arg::new("check")
+                    .short('c')
+                    .long("check")
+                    .help("정렬되어 있는지 확인만 함")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("buffer_size")
+                    .short('S')
+                    .long("buffer-size")
+                    .value_name("SIZE")
+                    .help("메모리에서 정렬할 크기(기본 단위는 K), 넘으면 임시 파일을 사용")
+                    .default_value("64M")
+                    .value_parser(parse_size),
+            )
+            .arg(
+                Arg::new("help")
+                    .long("help")
+                    .help("도움말 출력(-h는 --human-numeric-sort이다.)")
+                    .action(ArgAction::Help),
+            )
+            .arg(
+                Arg::new("version")
+                    .long("version")
+                    .help("버전 출력(-V는 --version-sort이다.)")
+                    .action(ArgAction::Version),
+            )
+            .arg(
+                Arg::new("derive_mode")
+                    .long("derive_mode")
+                    .hide(true)
+                    .help("`derive_mode`를 활성화하는 디버그용 플래그")
+                    .action(ArgAction::SetTrue),
+            )
+            .get_matches();
+
+        Args {
+            // `.default_value("-")`이므로 `unwrap`을 사용할 수 있다.
+            files: matches.get_many("files").unwrap().cloned().collect(),
+            numeric_sort: matches.get_flag("numeric_sort"),
+            human_numeric_sort: matches.get_flag("human_numeric_sort"),
+            version_sort: matches.get_flag("version_sort"),
+            ignore_case: matches.get_flag("ignore_case"),
+            ignore_leading_blanks: matches.get_flag("ignore_leading_blanks"),
+            reverse: matches.get_flag("reverse"),
+            unique: matches.get_flag("unique"),
+            stable: matches.get_flag("stable"),
+            key: matches
+                .get_many("key")
+                .map(|keys| keys.cloned().collect())
+                .unwrap_or_default(),
+            field_separator: matches.get_one("field_separator").copied(),
+            output: matches.get_one("output").cloned(),
+            check: matches.get_flag("check"),
+            // `.default_value("64M")`이므로 `unwrap`을 사용할 수 있다.
+            buffer_size: matches.get_one("buffer_size").copied().unwrap(),
+            help: None,
+            version: None,
+            derive_mode: matches.get_flag("derive_mode"),
+        }
+    }
+
+    /// 옵션이 없는 키에 적용하는 옵션
+    fn global_options(&self) -> KeyOptions {
+        let order = if self.numeric_sort {
+            Order::Numeric
+        } else if self.human_numeric_sort {
+            Order::Human
+        } else if self.version_sort {
+            Order::Version
+        } else {
+            Order::Byte
+        };
+
+        KeyOptions {
+            order,
+            ignore_blanks: self.ignore_leading_blanks,
+            ignore_case: self.ignore_case,
+            reverse: self.reverse,
+        }
+    }
+
+    fn comparator(&self) -> Result<Comparator, anyhow::Error> {
+        let separator = match self.field_separator {
+            Some(separator) if separator.is_ascii() => Some(separator as u8),
+            Some(separator) => anyhow::bail!("multi-byte field separator: '{separator}'"),
+            None => None,
+        };
+
+        // -u는 키가 같은 줄을 하나로 보므로 줄 전체를 비교하지 않는다.
+        Ok(Comparator::new(
+            self.key.clone(),
+            separator,
+            self.global_options(),
+            self.stable || self.unique,
+        ))
+    }
+
+    pub fn run(&self) -> Result<(), anyhow::Error> {
+        let comparator = self.comparator()?;
+        if self.check {
+            return self.check_order(&comparator);
+        }
+
+        let mut sorter = ExternalSorter::new(&comparator, self.unique, self.buffer_size);
+        for filename in &self.files {
+            for line in read_lines(filename)? {
+                sorter
+                    .push(line.map_err(|e| anyhow::anyhow!("{filename}: {e}"))?)
+                    .map_err(|e| anyhow::anyhow!("cannot create temporary file: {e}"))?;
+            }
+        }
+
+        // 입력을 모두 읽은 뒤에 열어야 -o로 입력 파일을 덮어쓸 수 있다.
+        let mut writer: Box<dyn Write> = match &self.output {
+            Some(output) => Box::new(BufWriter::new(
+                File::create(output).map_err(|e| anyhow::anyhow!("{output}: {e}"))?,
+            )),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        sorter.finish(&mut writer)?;
+
+        Ok(())
+    }
+
+    /// 처음으로 순서가 틀린 줄을 알리고 실패한다.
+    fn check_order(&self, comparator: &Comparator) -> Result<(), anyhow::Error> {
+        if let [_, extra, ..] = self.files.as_slice() {
+            anyhow::bail!("extra operand '{extra}' not allowed with -c");
+        }
+        let filename = &self.files[0];
+
+        let mut prev: Option<Vec<u8>> = None;
+        for (line_num, line) in read_lines(filename)?.enumerate() {
+            let line = line.map_err(|e| anyhow::anyhow!("{filename}: {e}"))?;
+            if let Some(prev) = &prev {
+                let order = comparator.compare(prev, &line);
+                // -u이면 같은 줄이 두 번 나와도 정렬되지 않은 것이다.
+                if order == Ordering::Greater || (self.unique && order == Ordering::Equal) {
+                    anyhow::bail!(
+                        "{filename}:{}: disorder: {}",
+                        line_num + 1,
+                        String::from_utf8_lossy(&line)
+                    );
+                }
+            }
+            prev = Some(line);
+        }
+
+        Ok(())
+    }
+}
+
+/// 파일을 열어서 줄 끝의 개행을 뺀 줄을 바이트 그대로 읽는다.
+fn read_lines(
+    filename: &str,
+) -> Result<impl Iterator<Item = Result<Vec<u8>, io::Error>>, anyhow::Error> {
+    let file = open(filename).map_err(|e| anyhow::anyhow!("{filename}: {e}"))?;
+    Ok(file.split(b'\n'))
+}
+
+/// 1024의 거듭제곱 단위(b, K, M, G, T)가 붙은 크기이다.
+/// 단위가 없으면 K이다.
+fn parse_size(s: &str) -> Result<usize, String> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let exponent = match unit {
+        "b" => 0,
+        "" | "k" | "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        _ => return Err(format!("invalid suffix in buffer size '{s}'")),
+    };
+
+    num.parse::<usize>()
+        .ok()
+        .and_then(|num| num.checked_mul(1024usize.checked_pow(exponent)?))
+        .filter(|&size| size > 0)
+        .ok_or_else(|| format!("invalid buffer size '{s}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_size;
+
+    #[test]
+    fn test_parse_size() {
+        pretty_assertions::assert_eq!(parse_size("10"), Ok(10 * 1024));
+        pretty_assertions::assert_eq!(parse_size("1b"), Ok(1));
+        pretty_assertions::assert_eq!(parse_size("2M"), Ok(2 * 1024 * 1024));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("").is_err());
+        assert!(parse_size("1X").is_err());
+        assert!(parse_size("99999999999999999999").is_err());
+        assert!(parse_size("99999999T").is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+
+use super::key::{KeyOptions, KeySpec, Order};
+
+/// 두 줄의 순서를 정한다.
+/// 정렬, 병합, --check가 모두 같은 비교를 사용한다.
+#[derive(Debug)]
+pub struct Comparator {
+    keys: Vec<KeySpec>,
+    separator: Option<u8>,
+    /// 옵션이 없는 키에 적용하는 옵션
+    global: KeyOptions,
+    /// 키가 같을 때 줄 전체를 비교하지 않는지 여부(-s, -u)
+    stable: bool,
+}
+
+impl Comparator {
+    pub fn new(
+        keys: Vec<KeySpec>,
+        separator: Option<u8>,
+        global: KeyOptions,
+        stable: bool,
+    ) -> Comparator {
+        let keys = if keys.is_empty() {
+            vec![KeySpec::whole_line()]
+        } else {
+            keys
+        };
+
+        Comparator {
+            keys,
+            separator,
+            global,
+            stable,
+        }
+    }
+
+    pub fn compare(&self, line1: &[u8], line2: &[u8]) -> Ordering {
+        for key in &self.keys {
+            let options = key.options.unwrap_or(self.global);
+            let key1 = key.extract(line1, self.separator, options.ignore_blanks);
+            let key2 = key.extract(line2, self.separator, options.ignore_blanks);
+            let order = compare_keys(key1, key2, &options);
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        if self.stable {
+            return Ordering::Equal;
+        }
+
+        // 키가 모두 같으면 줄 전체를 바이트 순서로 비교한다.
+        let order = line1.cmp(line2);
+        if self.global.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+fn compare_keys(key1: &[u8], key2: &[u8], options: &KeyOptions) -> Ordering {
+    let order = match options.order {
+        Order::Byte if options.ignore_case => key1
+            .iter()
+            .map(u8::to_ascii_uppercase)
+            .cmp(key2.iter().map(u8::to_ascii_uppercase)),
+        Order::Byte => key1.cmp(key2),
+        Order::Numeric => numeric_cmp(key1, key2),
+        Order::Human => human_cmp(key1, key2),
+        Order::Version => version_cmp(key1, key2),
+    };
+
+    if options.reverse {
+        order.reverse()
+    } else {
+        order
+    }
+}
+
+/// 키를 부호, 앞의 0을 뺀 정수부, 뒤의 0을 뺀 소수부, 숫자 뒤의 나머지로 나눈다.
+/// 나머지는 -h에서 단위를 읽는 데 쓴다. 키가 숫자로 시작하지 않으면 0으로 본다.
+fn parse_number(key: &[u8]) -> (bool, &[u8], &[u8], &[u8]) {
+    let key = key.trim_ascii_start();
+    let (negative, key) = match key.strip_prefix(b"-") {
+        Some(rest) => (true, rest),
+        None => (false, key),
+    };
+    let digits = |s: &[u8]| {
+        s.iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(s.len())
+    };
+    let (int, rest) = key.split_at(digits(key));
+    let (frac, rest) = match rest.strip_prefix(b".") {
+        Some(rest) => rest.split_at(digits(rest)),
+        None => (&rest[..0], rest),
+    };
+
+    let int = &int[int.iter().position(|&b| b != b'0').unwrap_or(int.len())..];
+    let frac = &frac[..frac
+        .iter()
+        .rposition(|&b| b != b'0')
+        .map_or(0, |pos| pos + 1)];
+    // 0에는 부호가 없으므로 -0과 0이 같은 키가 된다.
+    let negative = negative && !(int.is_empty() && frac.is_empty());
+
+    (negative, int, frac, rest)
+}
+
+/// -n 키를 비교한다. 수로 바꾸지 않으므로 자릿수에 제한이 없다.
+/// 정수부는 길이가 길수록 크고, 길이가 같으면 바이트 순서가 곧 수의 순서이다.
+fn numeric_cmp(key1: &[u8], key2: &[u8]) -> Ordering {
+    let (neg1, int1, frac1, _) = parse_number(key1);
+    let (neg2, int2, frac2, _) = parse_number(key2);
+    let magnitude = |int1: &[u8], frac1: &[u8], int2: &[u8], frac2: &[u8]| {
+        int1.len()
+            .cmp(&int2.len())
+            .then_with(|| int1.cmp(int2))
+            .then_with(|| frac1.cmp(frac2))
+    };
+
+    match (neg1, neg2) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => magnitude(int1, frac1, int2, frac2),
+        (true, true) => magnitude(int2, frac2, int1, frac1),
+    }
+}
+
+/// 부호와 단위로 먼저 비교하고 단위가 같으면 숫자로 비교한다(-h).
+/// 2K와 1000처럼 단위가 다르면 숫자의 크기는 보지 않는다.
+fn human_cmp(key1: &[u8], key2: &[u8]) -> Ordering {
+    fn unit_order(key: &[u8]) -> i32 {
+        const UNITS: &[u8] = b"KMGTPEZYRQ";
+        let (negative, int, frac, rest) = parse_number(key);
+        // 0에는 단위가 없다.
+        if int.is_empty() && frac.is_empty() {
+            return 0;
+        }
+        let order = match rest.first() {
+            Some(b'k') => 1,
+            Some(unit) => UNITS
+                .iter()
+                .position(|u| u == unit)
+                .map_or(0, |pos| pos as i32 + 1),
+            None => 0,
+        };
+
+        if negative { -order } else { order }
+    }
+
+    unit_order(key1)
+        .cmp(&unit_order(key2))
+        .then_with(|| numeric_cmp(key1, key2))
+}
+
+/// -V 키를 비교한다. 키를 글자 구간과 숫자 구간으로 번갈아 나눠서 앞에서부터 비교한다.
+/// 글자 구간에서는 `~`, 끝, 영문자, 그 밖의 기호 순이고 숫자 구간은 값으로 비교한다.
+fn version_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    fn order(c: Option<&u8>) -> i32 {
+        match c {
+            None => 0,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => *c as i32,
+            Some(b'~') => -1,
+            Some(c) => *c as i32 + 256,
+        }
+    }
+    let is_digit = |s: &[u8], i: usize| s.get(i).is_some_and(u8::is_ascii_digit);
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !is_digit(a, i)) || (j < b.len() && !is_digit(b, j)) {
+            let (c1, c2) = (order(a.get(i)), order(b.get(j)));
+            if c1 != c2 {
+                return c1.cmp(&c2);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+        // 숫자 구간이 더 긴 쪽이 크고, 길이가 같을 때만 처음 다른 자리를 본다.
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a, i) && is_digit(b, j) {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if is_digit(a, i) {
+            return Ordering::Greater;
+        }
+        if is_digit(b, j) {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{Comparator, human_cmp, numeric_cmp, version_cmp};
+    use crate::args::key::KeyOptions;
+
+    #[test]
+    fn test_numeric_cmp() {
+        pretty_assertions::assert_eq!(numeric_cmp(b"10", b"9"), Ordering::Greater);
+        pretty_assertions::assert_eq!(numeric_cmp(b"  -3", b"-20"), Ordering::Greater);
+        pretty_assertions::assert_eq!(numeric_cmp(b"1.50", b"1.5"), Ordering::Equal);
+        pretty_assertions::assert_eq!(numeric_cmp(b"-0", b"abc"), Ordering::Equal);
+        pretty_assertions::assert_eq!(numeric_cmp(b"007", b"7.0x"), Ordering::Equal);
+        pretty_assertions::assert_eq!(numeric_cmp(b"-.5", b"0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_human_cmp() {
+        pretty_assertions::assert_eq!(human_cmp(b"2K", b"1000"), Ordering::Greater);
+        pretty_assertions::assert_eq!(human_cmp(b"1k", b"2K"), Ordering::Less);
+        pretty_assertions::assert_eq!(human_cmp(b"1G", b"900M"), Ordering::Greater);
+        pretty_assertions::assert_eq!(human_cmp(b"-1G", b"-2K"), Ordering::Less);
+        pretty_assertions::assert_eq!(human_cmp(b"0K", b"0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_version_cmp() {
+        pretty_assertions::assert_eq!(version_cmp(b"a-1.10", b"a-1.9"), Ordering::Greater);
+        pretty_assertions::assert_eq!(version_cmp(b"a-1.01", b"a-1.1"), Ordering::Equal);
+        pretty_assertions::assert_eq!(version_cmp(b"1.0~rc1", b"1.0"), Ordering::Less);
+        pretty_assertions::assert_eq!(version_cmp(b"1.0a", b"1.0+"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_last_resort() {
+        let options = KeyOptions {
+            ignore_case: true,
+            ..Default::default()
+        };
+        // 키가 같으면 줄 전체를 비교한다.
+        let comparator = Comparator::new(Vec::new(), None, options, false);
+        pretty_assertions::assert_eq!(comparator.compare(b"A", b"a"), Ordering::Less);
+        // -s이면 같은 줄이다.
+        let comparator = Comparator::new(Vec::new(), None, options, true);
+        pretty_assertions::assert_eq!(comparator.compare(b"A", b"a"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,280 @@
+use std::str::FromStr;
+
+/// 키를 비교하는 방법
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Order {
+    /// 바이트 순서(LC_ALL=C)
+    #[default]
+    Byte,
+    /// 앞부분의 숫자(-n)
+    Numeric,
+    /// 단위가 붙은 숫자(-h)
+    Human,
+    /// 문자열 속의 숫자(-V)
+    Version,
+}
+
+/// 키를 비교할 때의 옵션
+/// `-k 2,2nr`처럼 키에 붙이거나 `-n -r`처럼 모든 키에 적용한다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyOptions {
+    pub order: Order,
+    /// 키 앞의 공백을 무시(b)
+    pub ignore_blanks: bool,
+    /// 대소문자를 구분하지 않음(f)
+    pub ignore_case: bool,
+    /// 역순(r)
+    pub reverse: bool,
+}
+
+/// 필드 번호와 필드 안의 글자 위치이다.
+/// 둘 다 1부터 시작한다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub field: usize,
+    /// 끝 위치에서 0이면 필드의 끝이다.
+    pub char: usize,
+}
+
+/// -k POS1[,POS2]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySpec {
+    pub start: Position,
+    /// 없으면 줄의 끝까지이다.
+    pub end: Option<Position>,
+    /// 키에 옵션이 없으면 전역 옵션을 따른다.
+    pub options: Option<KeyOptions>,
+}
+
+impl KeySpec {
+    /// -k가 없을 때 사용하는 줄 전체 키
+    pub fn whole_line() -> KeySpec {
+        KeySpec {
+            start: Position { field: 1, char: 1 },
+            end: None,
+            options: None,
+        }
+    }
+
+    /// 줄에서 키 부분을 잘라낸다.
+    /// 구분자가 없으면 필드는 앞의 공백을 포함한다.
+    pub fn extract<'a>(
+        &self,
+        line: &'a [u8],
+        separator: Option<u8>,
+        ignore_blanks: bool,
+    ) -> &'a [u8] {
+        let skip = |start: usize| {
+            if ignore_blanks {
+                skip_blanks(line, start)
+            } else {
+                start
+            }
+        };
+
+        // 오프셋이 아주 커도 줄 끝을 넘을 뿐이다.
+        let begin = skip(field_start(line, self.start.field, separator))
+            .saturating_add(self.start.char - 1);
+        let end = match self.end {
+            None => line.len(),
+            Some(Position { field, char: 0 }) => {
+                field_end(line, field_start(line, field, separator), separator)
+            }
+            Some(Position { field, char }) => {
+                skip(field_start(line, field, separator)).saturating_add(char)
+            }
+        }
+        .min(line.len());
+
+        if begin < end { &line[begin..end] } else { &[] }
+    }
+}
+
+impl FromStr for KeySpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pos1, pos2) = match s.split_once(',') {
+            Some((pos1, pos2)) => (pos1, Some(pos2)),
+            None => (s, None),
+        };
+        let mut options = None;
+        let start = parse_position(pos1, false, &mut options)
+            .map_err(|e| format!("{e}: invalid field specification '{s}'"))?;
+        let end = pos2
+            .map(|pos2| parse_position(pos2, true, &mut options))
+            .transpose()
+            .map_err(|e| format!("{e}: invalid field specification '{s}'"))?;
+
+        Ok(KeySpec {
+            start,
+            end,
+            options,
+        })
+    }
+}
+
+/// F[.C][OPTS]를 해석한다.
+/// 옵션 글자는 POS1과 POS2의 것을 합친다.
+fn parse_position(
+    s: &str,
+    is_end: bool,
+    options: &mut Option<KeyOptions>,
+) -> Result<Position, &'static str> {
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (pos, opts) = s.split_at(split);
+    let (field, char) = match pos.split_once('.') {
+        Some((field, char)) => (field, Some(char)),
+        None => (pos, None),
+    };
+
+    let field = field.parse().map_err(|_| "invalid number")?;
+    if field == 0 {
+        return Err("field number is zero");
+    }
+    let char = match char {
+        Some(char) => char.parse().map_err(|_| "invalid number after '.'")?,
+        None if is_end => 0,
+        None => 1,
+    };
+    if char == 0 && !is_end {
+        return Err("character offset is zero");
+    }
+
+    for c in opts.chars() {
+        let options = options.get_or_insert_default();
+        match c {
+            'b' => options.ignore_blanks = true,
+            'f' => options.ignore_case = true,
+            'r' => options.reverse = true,
+            'n' => options.order = Order::Numeric,
+            'h' => options.order = Order::Human,
+            'V' => options.order = Order::Version,
+            _ => return Err("invalid option"),
+        }
+    }
+
+    Ok(Position { field, char })
+}
+
+fn is_blank(b: &u8) -> bool {
+    matches!(b, b' ' | b'\t')
+}
+
+fn skip_blanks(line: &[u8], start: usize) -> usize {
+    start
+        + line[start..]
+            .iter()
+            .position(|b| !is_blank(b))
+            .unwrap_or(line.len() - start)
+}
+
+fn skip_nonblanks(line: &[u8], start: usize) -> usize {
+    start
+        + line[start..]
+            .iter()
+            .position(is_blank)
+            .unwrap_or(line.len() - start)
+}
+
+/// `field`번째 필드가 시작하는 위치
+fn field_start(line: &[u8], field: usize, separator: Option<u8>) -> usize {
+    let mut start = 0;
+    for _ in 1..field {
+        if start == line.len() {
+            break;
+        }
+        start = match separator {
+            Some(separator) => match line[start..].iter().position(|&b| b == separator) {
+                Some(pos) => start + pos + 1,
+                None => return line.len(),
+            },
+            None => skip_nonblanks(line, skip_blanks(line, start)),
+        };
+    }
+
+    start
+}
+
+/// `start`에서 시작하는 필드가 끝나는 위치
+fn field_end(line: &[u8], start: usize, separator: Option<u8>) -> usize {
+    match separator {
+        Some(separator) => line[start..]
+            .iter()
+            .position(|&b| b == separator)
+            .map_or(line.len(), |pos| start + pos),
+        None => skip_nonblanks(line, skip_blanks(line, start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyOptions, KeySpec, Order, Position};
+
+    #[test]
+    fn test_parse_key() {
+        pretty_assertions::assert_eq!(
+            "2".parse::<KeySpec>(),
+            Ok(KeySpec {
+                start: Position { field: 2, char: 1 },
+                end: None,
+                options: None,
+            })
+        );
+        pretty_assertions::assert_eq!(
+            "2.3,4".parse::<KeySpec>(),
+            Ok(KeySpec {
+                start: Position { field: 2, char: 3 },
+                end: Some(Position { field: 4, char: 0 }),
+                options: None,
+            })
+        );
+        // 두 위치의 옵션은 합친다.
+        pretty_assertions::assert_eq!(
+            "1b,1.5nr".parse::<KeySpec>(),
+            Ok(KeySpec {
+                start: Position { field: 1, char: 1 },
+                end: Some(Position { field: 1, char: 5 }),
+                options: Some(KeyOptions {
+                    order: Order::Numeric,
+                    ignore_blanks: true,
+                    ignore_case: false,
+                    reverse: true,
+                }),
+            })
+        );
+
+        assert!("0".parse::<KeySpec>().is_err());
+        assert!("1.0".parse::<KeySpec>().is_err());
+        assert!("a".parse::<KeySpec>().is_err());
+        assert!("1x".parse::<KeySpec>().is_err());
+        assert!("1,".parse::<KeySpec>().is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let extract = |key: &str, line: &str, separator: Option<u8>, blanks: bool| {
+            let key: KeySpec = key.parse().unwrap();
+            String::from_utf8(key.extract(line.as_bytes(), separator, blanks).to_vec()).unwrap()
+        };
+
+        // 구분자가 없으면 필드는 앞의 공백을 포함한다.
+        pretty_assertions::assert_eq!(extract("2,2", "a  bb c", None, false), "  bb");
+        pretty_assertions::assert_eq!(extract("2,2", "a  bb c", None, true), "bb");
+        pretty_assertions::assert_eq!(extract("2", "a  bb c", None, true), "bb c");
+        pretty_assertions::assert_eq!(extract("2.2,2.3", "a,bcd,e", Some(b','), false), "cd");
+        pretty_assertions::assert_eq!(extract("3,3", "a,,c", Some(b','), false), "c");
+        pretty_assertions::assert_eq!(extract("2,2", "a,,c", Some(b','), false), "");
+        // 없는 필드는 빈 키이다.
+        pretty_assertions::assert_eq!(extract("5", "a,b", Some(b','), false), "");
+        pretty_assertions::assert_eq!(extract("1.9", "abc", None, false), "");
+        pretty_assertions::assert_eq!(extract("2,1", "a b", None, false), "");
+        // 아주 큰 오프셋은 줄 끝으로 잘린다.
+        let max = usize::MAX;
+        pretty_assertions::assert_eq!(extract(&format!("2.{max}"), "a b", None, false), "");
+        pretty_assertions::assert_eq!(extract(&format!("1,1.{max}"), "a b", None, false), "a b");
+        pretty_assertions::assert_eq!(extract(&format!("{max}"), "a b", None, false), "");
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Seek, Write},
+    mem, vec,
+};
+
+use rayon::slice::ParallelSliceMut;
+
+use super::compare::Comparator;
+
+/// 한 번에 병합하는 묶음의 최대 개수
+/// 묶음이 더 많으면 여러 단계로 나누어 병합해서 열어 두는 임시 파일의 수를 제한한다.
+const MAX_MERGE: usize = 16;
+
+/// 정렬된 줄 묶음
+enum Run {
+    /// 마지막으로 읽은 묶음은 임시 파일에 쓰지 않는다.
+    Memory(vec::IntoIter<Vec<u8>>),
+    File(BufReader<File>),
+}
+
+impl Run {
+    fn next_line(&mut self) -> Result<Option<Vec<u8>>, io::Error> {
+        match self {
+            Run::Memory(lines) => Ok(lines.next()),
+            Run::File(file) => {
+                let mut line = Vec::new();
+                if file.read_until(b'\n', &mut line)? == 0 {
+                    return Ok(None);
+                }
+                line.pop();
+                Ok(Some(line))
+            }
+        }
+    }
+}
+
+/// 메모리에 다 담을 수 없는 입력을 정렬한다.
+/// 줄을 `buffer_size`만큼 모아서 정렬한 뒤 임시 파일에 쓰고, 마지막에 모든 묶음을 병합한다.
+pub struct ExternalSorter<'a> {
+    comparator: &'a Comparator,
+    unique: bool,
+    buffer_size: usize,
+    chunk: Vec<Vec<u8>>,
+    /// 지금 묶음이 차지하는 대략적인 메모리 크기
+    chunk_size: usize,
+    runs: Vec<Run>,
+}
+
+impl<'a> ExternalSorter<'a> {
+    pub fn new(comparator: &'a Comparator, unique: bool, buffer_size: usize) -> ExternalSorter<'a> {
+        ExternalSorter {
+            comparator,
+            unique,
+            buffer_size,
+            chunk: Vec::new(),
+            chunk_size: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, line: Vec<u8>) -> Result<(), io::Error> {
+        self.chunk_size += line.len() + mem::size_of::<Vec<u8>>();
+        self.chunk.push(line);
+        if self.chunk_size >= self.buffer_size {
+            self.spill()?;
+        }
+
+        Ok(())
+    }
+
+    /// 묶음을 여러 스레드로 정렬한다.
+    /// 안정 정렬이므로 같은 줄은 입력 순서를 유지하고, -u이면 처음 것만 남긴다.
+    fn sort_chunk(&mut self) -> Vec<Vec<u8>> {
+        let mut chunk = mem::take(&mut self.chunk);
+        self.chunk_size = 0;
+        chunk.par_sort_by(|line1, line2| self.comparator.compare(line1, line2));
+        if self.unique {
+            chunk.dedup_by(|line, prev| self.comparator.compare(prev, line) == Ordering::Equal);
+        }
+
+        chunk
+    }
+
+    /// 정렬한 묶음을 임시 파일에 쓴다.
+    fn spill(&mut self) -> Result<(), io::Error> {
+        let chunk = self.sort_chunk();
+        let mut writer = BufWriter::new(tempfile::tempfile()?);
+        for line in chunk {
+            writer.write_all(&line)?;
+            writer.write_all(b"\n")?;
+        }
+        self.runs.push(rewind(writer)?);
+
+        Ok(())
+    }
+
+    /// 모든 줄을 정렬한 순서로 출력한다.
+    pub fn finish(mut self, writer: &mut impl Write) -> Result<(), io::Error> {
+        let chunk = self.sort_chunk();
+        let mut runs = mem::take(&mut self.runs);
+        runs.push(Run::Memory(chunk.into_iter()));
+
+        // 이웃한 묶음끼리 병합해야 같은 줄의 입력 순서가 바뀌지 않는다.
+        while runs.len() > MAX_MERGE {
+            let mut merged = Vec::new();
+            let mut runs_iter = runs.into_iter();
+            loop {
+                let group: Vec<Run> = runs_iter.by_ref().take(MAX_MERGE).collect();
+                if group.is_empty() {
+                    break;
+                }
+                let mut writer = BufWriter::new(tempfile::tempfile()?);
+                self.merge(group, &mut writer)?;
+                merged.push(rewind(writer)?);
+            }
+            runs = merged;
+        }
+
+        self.merge(runs, writer)
+    }
+
+    /// 각 묶음의 첫 줄 중에서 가장 앞서는 줄을 차례대로 출력한다.
+    fn merge(&self, mut runs: Vec<Run>, writer: &mut impl Write) -> Result<(), io::Error> {
+        let mut heads = runs
+            .iter_mut()
+            .map(Run::next_line)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut prev: Option<Vec<u8>> = None;
+
+        loop {
+            // 같은 줄이면 앞의 묶음, 즉 먼저 입력된 줄을 고른다.
+            let min = (0..heads.len())
+                .filter(|&i| heads[i].is_some())
+                .reduce(|min, i| {
+                    match self.comparator.compare(
+                        heads[i].as_deref().unwrap_or_default(),
+                        heads[min].as_deref().unwrap_or_default(),
+                    ) {
+                        Ordering::Less => i,
+                        _ => min,
+                    }
+                });
+            let Some(min) = min else {
+                break;
+            };
+            // 위에서 `Some`인 것만 골랐으므로 `unwrap`을 사용할 수 있다.
+            let line = mem::replace(&mut heads[min], runs[min].next_line()?).unwrap();
+
+            if self.unique {
+                if prev
+                    .as_ref()
+                    .is_some_and(|prev| self.comparator.compare(prev, &line) == Ordering::Equal)
+                {
+                    continue;
+                }
+                writer.write_all(&line)?;
+                writer.write_all(b"\n")?;
+                prev = Some(line);
+            } else {
+                writer.write_all(&line)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+/// 다 쓴 임시 파일을 처음부터 다시 읽는다.
+fn rewind(writer: BufWriter<File>) -> Result<Run, io::Error> {
+    let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.rewind()?;
+
+    Ok(Run::File(BufReader::new(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExternalSorter;
+    use crate::args::{compare::Comparator, key::KeyOptions};
+
+    fn sort(lines: &[&str], comparator: &Comparator, unique: bool, buffer_size: usize) -> String {
+        let mut sorter = ExternalSorter::new(comparator, unique, buffer_size);
+        for line in lines {
+            sorter.push(line.as_bytes().to_vec()).unwrap();
+        }
+        let mut output = Vec::new();
+        sorter.finish(&mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_external_sort() {
+        let lines: Vec<String> = (0..100).map(|n| ((n * 37) % 100).to_string()).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let options = KeyOptions {
+            order: crate::args::key::Order::Numeric,
+            ..Default::default()
+        };
+        let comparator = Comparator::new(Vec::new(), None, options, false);
+        let expected: String = (0..100).map(|n| format!("{n}\n")).collect();
+
+        // 메모리에서 모두 정렬한다.
+        pretty_assertions::assert_eq!(sort(&lines, &comparator, false, 1 << 20), expected);
+        // 줄마다 임시 파일을 만들어서 여러 단계로 병합한다.
+        pretty_assertions::assert_eq!(sort(&lines, &comparator, false, 1), expected);
+    }
+
+    #[test]
+    fn test_external_sort_stable_unique() {
+        let options = KeyOptions {
+            ignore_case: true,
+            ..Default::default()
+        };
+        let comparator = Comparator::new(Vec::new(), None, options, true);
+        let lines = ["b", "B", "a", "A", "b"];
+
+        // 묶음을 나누어도 같은 줄은 입력 순서를 유지한다.
+        pretty_assertions::assert_eq!(sort(&lines, &comparator, false, 30), "a\nA\nb\nB\nb\n");
+        pretty_assertions::assert_eq!(sort(&lines, &comparator, true, 30), "a\nb\n");
+        pretty_assertions::assert_eq!(sort(&lines, &comparator, true, 1 << 20), "a\nb\n");
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use rand::{Rng, distr::Alphanumeric};
+use std::fs;
+
+const PRG: &str = "sortr";
+const WORDS: &str = "tests/inputs/words.txt";
+const NUMBERS: &str = "tests/inputs/numbers.txt";
+const SIZES: &str = "tests/inputs/sizes.txt";
+const VERSIONS: &str = "tests/inputs/versions.txt";
+const TABLE: &str = "tests/inputs/table.csv";
+const FIELDS: &str = "tests/inputs/fields.txt";
+
+// --------------------------------------------------
+fn gen_bad_file() -> String {
+    loop {
+        let filename: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(7)
+            .map(char::from)
+            .collect();
+
+        if fs::metadata(&filename).is_err() {
+            return filename;
+        }
+    }
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = format!("{bad}: .* [(]os error 2[)]");
+    Command::cargo_bin(PRG)?
+        .args([WORDS, &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::is_match(expected)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_key() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-k", "0", WORDS])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("field number is zero"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_numeric_and_human() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "-h", NUMBERS])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> Result<()> {
+    let expected = fs::read_to_string(expected_file)?;
+    let output = Command::cargo_bin(PRG)?.args(args).output().expect("fail");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("invalid UTF-8");
+    assert_eq!(stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read_to_string(WORDS)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/words.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn words() -> Result<()> {
+    run(&[WORDS], "tests/expected/words.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_r() -> Result<()> {
+    run(&[WORDS, "-r"], "tests/expected/words.txt.r.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_u() -> Result<()> {
+    run(&[WORDS, "-u"], "tests/expected/words.txt.u.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_f() -> Result<()> {
+    // -f는 대문자로 바꾸어 비교하므로 `_`가 글자보다 뒤에 온다.
+    run(&[WORDS, "-f"], "tests/expected/words.txt.f.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_fu() -> Result<()> {
+    run(&[WORDS, "-fu"], "tests/expected/words.txt.fu.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn words_fs() -> Result<()> {
+    run(
+        &[WORDS, "--ignore-case", "--stable"],
+        "tests/expected/words.txt.fs.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn words_b() -> Result<()> {
+    run(&[WORDS, "-b"], "tests/expected/words.txt.b.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_n() -> Result<()> {
+    run(&[NUMBERS, "-n"], "tests/expected/numbers.txt.n.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_nr() -> Result<()> {
+    run(&[NUMBERS, "-nr"], "tests/expected/numbers.txt.nr.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn numbers_nu() -> Result<()> {
+    run(
+        &[NUMBERS, "--numeric-sort", "--unique"],
+        "tests/expected/numbers.txt.nu.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn sizes_h() -> Result<()> {
+    run(&[SIZES, "-h"], "tests/expected/sizes.txt.h.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sizes_hr() -> Result<()> {
+    run(
+        &[SIZES, "--human-numeric-sort", "--reverse"],
+        "tests/expected/sizes.txt.hr.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn versions_v() -> Result<()> {
+    run(&[VERSIONS, "-V"], "tests/expected/versions.txt.V.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn table_k2() -> Result<()> {
+    run(
+        &[TABLE, "-t", ",", "-k", "2,2"],
+        "tests/expected/table.csv.k2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn table_k2_s() -> Result<()> {
+    run(
+        &[TABLE, "-t", ",", "-k", "2,2", "-s"],
+        "tests/expected/table.csv.k2.s.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn table_k2_u() -> Result<()> {
+    run(
+        &[TABLE, "-t", ",", "-k", "2,2", "-u"],
+        "tests/expected/table.csv.k2.u.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn table_k2_k3nr() -> Result<()> {
+    run(
+        &[TABLE, "-t", ",", "-k", "2,2", "-k", "3,3nr"],
+        "tests/expected/table.csv.k2.k3nr.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn table_k1_2() -> Result<()> {
+    run(
+        &[TABLE, "--field-separator", ",", "--key", "1.2,1.3"],
+        "tests/expected/table.csv.k1.2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn huge_char_offset() -> Result<()> {
+    // 오프셋이 줄보다 길면 키가 비어서 줄 전체로 비교한다.
+    for key in ["2.18446744073709551615", "1,1.18446744073709551615"] {
+        Command::cargo_bin(PRG)?
+            .args(["-k", key])
+            .write_stdin("x b\ny a\nw c\n")
+            .assert()
+            .success()
+            .stdout("w c\nx b\ny a\n");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fields_k2() -> Result<()> {
+    // -t가 없으면 필드는 앞의 공백을 포함한다.
+    run(&[FIELDS, "-k", "2,2"], "tests/expected/fields.txt.k2.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fields_k2b() -> Result<()> {
+    run(&[FIELDS, "-k", "2b,2"], "tests/expected/fields.txt.k2b.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fields_k2n_k1r() -> Result<()> {
+    run(
+        &[FIELDS, "-k2,2n", "-k1,1r"],
+        "tests/expected/fields.txt.k2n.k1r.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn words_numbers() -> Result<()> {
+    run(
+        &[WORDS, NUMBERS],
+        "tests/expected/words.txt.numbers.txt.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn buffer_size_spill() -> Result<()> {
+    // 줄마다 임시 파일에 쓰고 병합해도 결과는 같다.
+    run(
+        &[WORDS, NUMBERS, "-S", "1b"],
+        "tests/expected/words.txt.numbers.txt.out",
+    )?;
+    run(
+        &[TABLE, "-t", ",", "-k", "2,2", "-s", "-S", "1b"],
+        "tests/expected/table.csv.k2.s.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn output_overwrites_input() -> Result<()> {
+    let file = tempfile::NamedTempFile::new()?;
+    fs::copy(WORDS, file.path())?;
+    let path = file.path().to_str().unwrap();
+
+    Command::cargo_bin(PRG)?
+        .args(["-o", path, path])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(
+        fs::read_to_string(path)?,
+        fs::read_to_string("tests/expected/words.txt.out")?
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_sorted() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "tests/expected/words.txt.out"])
+        .assert()
+        .success()
+        .stdout("");
+    Command::cargo_bin(PRG)?
+        .args(["--check", "-n", "tests/expected/numbers.txt.n.out"])
+        .assert()
+        .success();
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_disorder() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-c", WORDS])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(format!("{WORDS}:2: disorder: Apple\n"));
+    // -u이면 같은 줄이 있어도 정렬되지 않은 것이다.
+    Command::cargo_bin(PRG)?
+        .args(["-cu", "tests/expected/words.txt.out"])
+        .assert()
+        .failure()
+        .stderr("tests/expected/words.txt.out:11: disorder: banana\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_check_extra_operand() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-c", WORDS, NUMBERS])
+        .assert()
+        .failure()
+        .stderr(format!("extra operand '{NUMBERS}' not allowed with -c\n"));
+    Ok(())
+}
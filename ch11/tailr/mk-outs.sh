@@ -47,3 +47,18 @@ tail -c +3 $ALL > $OUT_DIR/all.c+3.out
 tail -c +8 $ALL > $OUT_DIR/all.c+8.out
 tail -c +12 $ALL > $OUT_DIR/all.c+12.out
 tail -n +3 -q $ALL > $OUT_DIR/all.n+3.q.out
+
+# 파이프로 받은 표준 입력
+cat $ROOT/two.txt | tail -n 1 $ROOT/one.txt - > $OUT_DIR/stdin.n1.out
+
+# UTF-8이 아닌 바이트와 NUL로 끝나는 레코드
+BIN="$ROOT/records.bin"
+tail -n 4 $BIN > $OUT_DIR/records.bin.n4.out
+tail -c 20 $BIN > $OUT_DIR/records.bin.c20.out
+tail -z -n 2 $BIN > $OUT_DIR/records.bin.z.n2.out
+tail -z -n +2 $BIN > $OUT_DIR/records.bin.z.n+2.out
+
+# 헤더를 강제하는 -v와 단위를 붙인 수
+tail -v -n 1 $ROOT/one.txt > $OUT_DIR/one.txt.v.n1.out
+tail -q -v -n 1 $ROOT/one.txt $ROOT/two.txt > $OUT_DIR/all.q.v.n1.out
+tail -c 1b $ROOT/twelve.txt > $OUT_DIR/twelve.txt.c1b.out
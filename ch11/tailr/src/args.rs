@@ -1,26 +1,67 @@
 use std::{
-    fs::File,
-    io::{self, BufReader, BufWriter, Write},
+    io::{self, BufReader, BufWriter, Seek, SeekFrom, Write},
     str::FromStr,
+    time::Duration,
 };
 
-use clap::{Arg, ArgAction, ArgGroup, Command, Parser};
+use clap::{Arg, ArgAction, ArgGroup, Command, Parser, value_parser};
 
-use crate::{count_lines_bytes, get_start_index, print_bytes, print_lines};
+use crate::{
+    follow::{FollowMode, Followed, Follower},
+    get_start_index, open, print_bytes, print_lines, stream_bytes, stream_lines,
+};
 
 /// `tail`의 간단한 러스트 버전
 #[derive(Debug, Parser)]
 #[command(version, about, author)]
 pub struct Args {
-    /// 입력 파일(들)
-    #[arg(required(true), num_args(1..), value_name("FILE"))]
+    /// 입력 파일(들), `-`는 표준 입력
+    #[arg(default_value("-"), num_args(1..), value_name("FILE"))]
     files: Vec<String>,
 
     /// 헤더를 표시하지 않음
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with("verbose"))]
     quiet: bool,
+    /// 파일이 하나여도 헤더를 표시
+    #[arg(short, long, overrides_with("quiet"))]
+    verbose: bool,
+    /// 줄이 개행이 아니라 NUL로 끝남
+    #[arg(short, long)]
+    zero_terminated: bool,
     #[command(flatten)]
     counter: Counter,
+
+    /// 파일 끝에 추가되는 내용을 계속 출력(-f는 --follow=descriptor)
+    #[arg(
+        short,
+        long,
+        value_name("HOW"),
+        num_args(0..=1),
+        require_equals(true),
+        default_missing_value("descriptor")
+    )]
+    follow: Option<FollowMode>,
+    /// --follow=name --retry와 같음
+    #[arg(short('F'))]
+    follow_name_retry: bool,
+    /// 열 수 없는 파일을 계속 다시 열어 봄
+    #[arg(long)]
+    retry: bool,
+    /// 파일을 확인하는 간격(초)
+    #[arg(
+        short,
+        long,
+        value_name("N"),
+        default_value("1.0"),
+        value_parser(parse_interval)
+    )]
+    sleep_interval: Duration,
+    /// PID 프로세스가 끝나면 멈춤
+    #[arg(long, value_name("PID"), value_parser(value_parser!(i32).range(1..)))]
+    pid: Option<i32>,
+    /// inotify를 사용하지 않고 --sleep-interval마다 확인
+    #[arg(long, hide(true))]
+    disable_inotify: bool,
 }
 
 #[derive(Debug, clap::Args)]
@@ -53,10 +94,10 @@ impl Args {
             .about("`tail`의 간단한 러스트 버전")
             .arg(
                 Arg::new("files")
-                    .required(true)
+                    .default_value("-")
                     .num_args(1..)
                     .value_name("FILE")
-                    .help("입력 파일(들)"),
+                    .help("입력 파일(들), `-`는 표준 입력"),
             )
             .arg(
                 Arg::new("lines")
@@ -80,13 +121,75 @@ impl Args {
                     .short('q')
                     .long("quiet")
                     .action(ArgAction::SetTrue)
+                    .overrides_with("verbose")
                     .help("헤더를 표시하지 않음"),
             )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::SetTrue)
+                    .overrides_with("quiet")
+                    .help("파일이 하나여도 헤더를 표시"),
+            )
+            .arg(
+                Arg::new("zero_terminated")
+                    .short('z')
+                    .long("zero-terminated")
+                    .action(ArgAction::SetTrue)
+                    .help("줄이 개행이 아니라 NUL로 끝남"),
+            )
+            .arg(
+                Arg::new("follow")
+                    .short('f')
+                    .long("follow")
+                    .value_name("HOW")
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("descriptor")
+                    .help("파일 끝에 추가되는 내용을 계속 출력(-f는 --follow=descriptor)")
+                    .value_parser(value_parser!(FollowMode)),
+            )
+            .arg(
+                Arg::new("follow_name_retry")
+                    .short('F')
+                    .action(ArgAction::SetTrue)
+                    .help("--follow=name --retry와 같음"),
+            )
+            .arg(
+                Arg::new("retry")
+                    .long("retry")
+                    .action(ArgAction::SetTrue)
+                    .help("열 수 없는 파일을 계속 다시 열어 봄"),
+            )
+            .arg(
+                Arg::new("sleep_interval")
+                    .short('s')
+                    .long("sleep-interval")
+                    .value_name("N")
+                    .default_value("1.0")
+                    .help("파일을 확인하는 간격(초)")
+                    .value_parser(parse_interval),
+            )
+            .arg(
+                Arg::new("pid")
+                    .long("pid")
+                    .value_name("PID")
+                    .help("PID 프로세스가 끝나면 멈춤")
+                    .value_parser(value_parser!(i32).range(1..)),
+            )
+            .arg(
+                Arg::new("disable_inotify")
+                    .long("disable-inotify")
+                    .hide(true)
+                    .action(ArgAction::SetTrue)
+                    .help("inotify를 사용하지 않고 --sleep-interval마다 확인"),
+            )
             .group(ArgGroup::new("counter").args(["lines", "bytes"]))
             .get_matches();
 
         Args {
-            // `.num_args(1..)`이므로 `unwrap`을 사용할 수 있다.
+            // `.default_value("-")`이므로 `unwrap`을 사용할 수 있다.
             files: matches.get_many("files").unwrap().cloned().collect(),
             counter: Counter {
                 // `.default_value(10)`이므로 `unwrap`을 사용할 수 있다.
@@ -95,66 +198,158 @@ impl Args {
             },
 
             quiet: matches.get_flag("quiet"),
+            verbose: matches.get_flag("verbose"),
+            zero_terminated: matches.get_flag("zero_terminated"),
+            follow: matches.get_one("follow").copied(),
+            follow_name_retry: matches.get_flag("follow_name_retry"),
+            retry: matches.get_flag("retry"),
+            // `.default_value("1.0")`이므로 `unwrap`을 사용할 수 있다.
+            sleep_interval: matches.get_one("sleep_interval").copied().unwrap(),
+            pid: matches.get_one("pid").copied(),
+            disable_inotify: matches.get_flag("disable_inotify"),
+        }
+    }
+
+    /// -F는 --follow=name이다.
+    fn follow_mode(&self) -> Option<FollowMode> {
+        if self.follow_name_retry {
+            Some(FollowMode::Name)
+        } else {
+            self.follow
         }
     }
 
     /// 책의 내용일 일부 반영해서 코드를 단순화했다.
     pub fn run(&self) -> Result<(), anyhow::Error> {
+        let follow = self.follow_mode();
+        // 표준 입력은 다시 열 수 없다.
+        if follow == Some(FollowMode::Name) && self.files.iter().any(|filename| filename == "-") {
+            anyhow::bail!("cannot follow '-' by name");
+        }
         // 루프에 진입할 필요가 없다면 루프에 진입하지 않고 미리 반환한다.
-        if let Some(ref bytes_take) = self.counter.bytes {
-            if let TakeValue::TakeNum(0) = bytes_take {
+        // 따라갈 때는 -n 0이어도 추가되는 내용을 출력해야 한다.
+        if follow.is_none() {
+            if self.pid.is_some() {
+                eprintln!("warning: PID ignored; --pid=PID is useful only when following");
+            }
+            if let Some(ref bytes_take) = self.counter.bytes {
+                if let TakeValue::TakeNum(0) = bytes_take {
+                    return Ok(());
+                }
+            } else if let TakeValue::TakeNum(0) = self.counter.lines {
                 return Ok(());
             }
-        } else if let TakeValue::TakeNum(0) = self.counter.lines {
-            return Ok(());
         }
 
+        let delimiter = if self.zero_terminated { b'\0' } else { b'\n' };
         let mut b_stdout = BufWriter::new(io::stdout().lock());
-
-        self.files
-            .iter()
-            .enumerate()
-            .try_for_each(|(file_num, filename)| {
-                // 겹치는 코드를 통합했다.
-                let mut b_f = BufReader::new(match File::open(filename) {
-                    Ok(f) => f,
-                    // 파일을 여는데 실패했을 때는 오류를 인쇄하고 다음 파일로 넘어간다.
-                    Err(e) => {
-                        eprintln!("{filename}: {e}");
-                        return Ok(());
+        // -q와 -v는 나중에 준 것을 따른다.
+        let is_header_print = self.verbose || (!self.quiet && self.files.len() > 1);
+        // 처음 출력을 마친 뒤에 계속 읽을 파일들
+        let mut followed = Vec::new();
+        // 마지막으로 헤더를 출력한 파일
+        let mut last_printed = None;
+
+        for filename in &self.files {
+            let display = match filename.as_str() {
+                "-" => "standard input",
+                _ => filename,
+            };
+            // 겹치는 코드를 통합했다.
+            let mut file = match open(filename) {
+                Ok(f) => f,
+                // 파일을 여는데 실패했을 때는 오류를 인쇄하고 다음 파일로 넘어간다.
+                Err(e) => {
+                    eprintln!("{display}: {e}");
+                    // --retry이면 나중에 다시 열어 본다.
+                    if follow.is_some() {
+                        followed.push(Followed::new(filename, None, 0)?);
                     }
+                    continue;
+                }
+            };
+            // 일반 파일이 아니면 끝에서부터 읽을 수 없으므로 처음부터 읽는다.
+            let seekable = file.metadata().is_ok_and(|meta| meta.is_file());
+            // 리다이렉트한 표준 입력은 앞에서 읽고 남은 부분부터가 입력이다.
+            let start = file.stream_position().unwrap_or(0);
+            // `/proc`의 파일처럼 일반 파일이어도 크기가 0이면 끝을 믿을 수 없다.
+            // GNU처럼 끝이 시작보다 뒤에 있을 때만 끝에서부터 읽는다.
+            let backward = seekable
+                && file.seek(SeekFrom::End(0)).is_ok_and(|end| end > start)
+                && file.seek(SeekFrom::Start(start)).is_ok();
+            let mut b_f = BufReader::new(file);
+            // 앞의 파일과 구분하는 빈 줄은 헤더 앞에 출력한다.
+            // 마지막 파일을 열지 못해도 빈 줄이 남지 않는다.
+            if is_header_print {
+                if last_printed.is_some() {
+                    writeln!(b_stdout)?;
+                }
+                writeln!(b_stdout, "==> {display} <==")?
+            }
+            // 따라가지 않는 입력은 따라가는 파일과 겹치지 않는 번호로 기록한다.
+            // 다음에 따라가는 파일의 내용을 출력할 때 헤더를 다시 출력한다.
+            last_printed = Some(if seekable { followed.len() } else { usize::MAX });
+
+            if !backward {
+                let result = match &self.counter.bytes {
+                    Some(bytes) => stream_bytes(&mut b_f, bytes, &mut b_stdout),
+                    None => stream_lines(&mut b_f, &self.counter.lines, delimiter, &mut b_stdout),
+                };
+                if let Err(e) = result {
+                    eprintln!("{display}: {e}");
+                }
+            } else if let Some(bytes) = &self.counter.bytes {
+                // 파일의 크기는 읽지 않고도 알 수 있다.
+                let result = b_f.seek(SeekFrom::End(0)).and_then(|end| {
+                    let start_idx = get_start_index(bytes, (end - start) as i64);
+                    print_bytes(&mut b_f, start_idx.map(|idx| start + idx), &mut b_stdout)
                 });
-                // 약간의 오버헤드가 있다.
-                let (total_lines, total_bytes) = count_lines_bytes(&mut b_f)?;
-
-                let is_header_print = !self.quiet && self.files.len() > 1;
-                if is_header_print {
-                    writeln!(b_stdout, "==> {filename} <==")?
+                if let Err(e) = result {
+                    eprintln!("{display}: {e}");
                 }
-
-                if let Some(bytes) = &self.counter.bytes {
-                    let start_idx = get_start_index(bytes, total_bytes);
-                    if let Err(e) = print_bytes(&mut b_f, start_idx, &mut b_stdout) {
-                        eprintln!("{filename}: {e}");
-                    }
-                } else {
-                    let start_idx = get_start_index(&self.counter.lines, total_lines);
-                    if let Err(e) = print_lines(&mut b_f, start_idx, &mut b_stdout) {
-                        eprintln!("{filename}: {e}");
-                    }
+            } else {
+                if let Err(e) = print_lines(&mut b_f, &self.counter.lines, delimiter, &mut b_stdout)
+                {
+                    eprintln!("{display}: {e}");
                 }
+            }
 
-                // self.files.len() >= 1 일때 루프에 진입할 수 있다.
-                // 따라서 1을 빼도 문제가 발생하지 않아야 한다.
-                if is_header_print && self.files.len() - 1 > file_num {
-                    writeln!(b_stdout)?;
+            // 파이프는 끝까지 읽었으므로 따라가지 않는다.
+            if follow.is_some() && seekable {
+                match b_f.stream_position() {
+                    Ok(pos) => followed.push(Followed::new(display, Some(b_f.into_inner()), pos)?),
+                    Err(e) => eprintln!("{display}: {e}"),
                 }
+            }
+        }
+
+        if let Some(mode) = follow
+            && !followed.is_empty()
+        {
+            Follower::new(
+                followed,
+                mode,
+                self.retry || self.follow_name_retry,
+                self.sleep_interval,
+                self.pid,
+                is_header_print.then_some(last_printed),
+                self.disable_inotify,
+            )
+            .run(&mut b_stdout)?;
+        }
 
-                Result::<(), anyhow::Error>::Ok(())
-            })
+        Ok(())
     }
 }
 
+/// 0 이상의 초, 소수도 가능하다.
+fn parse_interval(s: &str) -> Result<Duration, anyhow::Error> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| anyhow::anyhow!("invalid number of seconds: '{s}'"))
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TakeValue {
     PlusZero,
@@ -164,21 +359,64 @@ pub enum TakeValue {
 impl FromStr for TakeValue {
     type Err = anyhow::Error;
 
+    /// `2K`, `+1MiB`처럼 단위를 붙일 수 있다.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "+0" => Ok(TakeValue::PlusZero),
-            _ => {
-                let num = s.parse::<i64>().map_err(|_| anyhow::anyhow!("{s}"))?;
-                if s.starts_with(['-', '+']) {
-                    Ok(TakeValue::TakeNum(num))
-                } else {
-                    Ok(TakeValue::TakeNum(-num))
-                }
-            }
+        let (plus, rest) = match s.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('-').unwrap_or(s)),
+        };
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (digits, suffix) = rest.split_at(split);
+        if digits.is_empty() {
+            anyhow::bail!("{s}");
+        }
+        let multiplier = parse_multiplier(suffix).ok_or_else(|| anyhow::anyhow!("{s}"))?;
+
+        // i64::MIN도 받을 수 있도록 절댓값을 u64로 계산한다.
+        let too_large = || anyhow::anyhow!("{s}: value too large");
+        let num = digits
+            .parse::<u64>()
+            .ok()
+            .and_then(|num| num.checked_mul(multiplier))
+            .ok_or_else(too_large)?;
+        match (plus, num) {
+            (true, 0) => Ok(TakeValue::PlusZero),
+            (true, num) => Ok(TakeValue::TakeNum(
+                i64::try_from(num).map_err(|_| too_large())?,
+            )),
+            (false, num) => Ok(TakeValue::TakeNum(
+                0i64.checked_sub_unsigned(num).ok_or_else(too_large)?,
+            )),
         }
     }
 }
 
+/// GNU `tail`의 단위
+/// `b`는 512, `K`는 1024, `KB`는 1000, `KiB`는 1024이다. M, G, T, P, E, Z, Y도 같다.
+fn parse_multiplier(suffix: &str) -> Option<u64> {
+    const UNITS: &str = "KMGTPEZY";
+
+    let mut chars = suffix.chars();
+    let unit = match chars.next() {
+        None => return Some(1),
+        Some('b') if chars.as_str().is_empty() => return Some(512),
+        Some('k') => 'K',
+        Some('m') => 'M',
+        Some(unit) => unit,
+    };
+    let exponent = UNITS.find(unit)? as u32 + 1;
+    let base: u64 = match chars.as_str() {
+        "" | "iB" => 1024,
+        "B" => 1000,
+        _ => return None,
+    };
+
+    // Z와 Y는 u64를 넘어선다.
+    Some(base.checked_pow(exponent).unwrap_or(u64::MAX))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::args::TakeValue;
@@ -236,5 +474,45 @@ mod tests {
         let res = "abc".parse::<TakeValue>();
         assert!(res.is_err());
         pretty_assertions::assert_eq!(res.unwrap_err().to_string(), "abc");
+
+        // 단위를 붙이면 곱한다.
+        for (s, num) in [
+            ("2b", -1024),
+            ("2K", -2048),
+            ("2k", -2048),
+            ("2KiB", -2048),
+            ("2kB", -2000),
+            ("2KB", -2000),
+            ("-1M", -1 << 20),
+            ("1m", -1 << 20),
+            ("1MB", -1_000_000),
+            ("+1MiB", 1 << 20),
+            ("+1G", 1 << 30),
+            ("1T", -1 << 40),
+            ("1PB", -1_000_000_000_000_000),
+            ("-8E", i64::MIN),
+            ("+7E", 7 << 60),
+        ] {
+            pretty_assertions::assert_eq!(s.parse::<TakeValue>().unwrap(), TakeValue::TakeNum(num));
+        }
+        // +0에 단위를 붙여도 +0이다.
+        pretty_assertions::assert_eq!("+0K".parse::<TakeValue>().unwrap(), TakeValue::PlusZero);
+
+        // 알 수 없는 단위는 유효하지 않다.
+        for s in ["1g", "1B", "1Ki", "1bB", "1KiBB", "1x", "K", "+", ""] {
+            let res = s.parse::<TakeValue>();
+            assert!(res.is_err(), "{s}");
+            pretty_assertions::assert_eq!(res.unwrap_err().to_string(), s);
+        }
+
+        // 범위를 넘어서면 유효하지 않다.
+        for s in ["+8E", "1Z", "1Y", "99999999999999999999"] {
+            let res = s.parse::<TakeValue>();
+            assert!(res.is_err(), "{s}");
+            pretty_assertions::assert_eq!(
+                res.unwrap_err().to_string(),
+                format!("{s}: value too large")
+            );
+        }
     }
 }
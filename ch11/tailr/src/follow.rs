@@ -0,0 +1,281 @@
+use std::{
+    fs::{self, File},
+    io::{self, Seek, SeekFrom, Write},
+    os::unix::fs::MetadataExt,
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use nix::{errno::Errno, sys::signal, unistd::Pid};
+
+/// --follow=HOW
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FollowMode {
+    /// 처음 연 파일을 계속 읽는다(-f).
+    Descriptor,
+    /// 같은 이름의 파일을 읽는다. 로그 파일을 교체해도 새 파일을 따라간다(-F).
+    Name,
+}
+
+/// 따라가는 파일 하나
+pub struct Followed {
+    name: String,
+    file: Option<File>,
+    /// 지금까지 출력한 위치
+    pos: u64,
+    /// 파일이 교체되었는지 확인하기 위한 (장치, inode)
+    id: Option<(u64, u64)>,
+}
+
+impl Followed {
+    /// 처음 출력을 마친 파일이다.
+    /// 열지 못한 파일은 `file`이 `None`이다.
+    pub fn new(name: &str, file: Option<File>, pos: u64) -> Result<Followed, io::Error> {
+        let id = match &file {
+            Some(file) => Some(file_id(&file.metadata()?)),
+            None => None,
+        };
+
+        Ok(Followed {
+            name: name.to_string(),
+            file,
+            pos,
+            id,
+        })
+    }
+}
+
+fn file_id(meta: &fs::Metadata) -> (u64, u64) {
+    (meta.dev(), meta.ino())
+}
+
+/// 파일 끝에 추가되는 내용을 계속 출력한다.
+pub struct Follower {
+    files: Vec<Followed>,
+    mode: FollowMode,
+    /// 열 수 없는 파일도 계속 다시 열어 본다.
+    retry: bool,
+    sleep_interval: Duration,
+    /// 이 프로세스가 끝나면 멈춘다.
+    pid: Option<i32>,
+    /// 헤더를 출력할 때 마지막으로 출력한 파일
+    /// 다른 파일의 내용을 출력할 때 헤더를 다시 출력한다.
+    headers: Option<Option<usize>>,
+    watcher: Watcher,
+}
+
+impl Follower {
+    pub fn new(
+        files: Vec<Followed>,
+        mode: FollowMode,
+        retry: bool,
+        sleep_interval: Duration,
+        pid: Option<i32>,
+        headers: Option<Option<usize>>,
+        disable_inotify: bool,
+    ) -> Follower {
+        let mut watcher = Watcher::new(disable_inotify);
+        for followed in &files {
+            watcher.watch(Path::new(&followed.name));
+        }
+
+        Follower {
+            files,
+            mode,
+            retry,
+            sleep_interval,
+            pid,
+            headers,
+            watcher,
+        }
+    }
+
+    pub fn run<W: Write>(mut self, writer: &mut W) -> Result<(), anyhow::Error> {
+        loop {
+            // 프로세스가 끝났더라도 그때까지 추가된 내용은 출력한다.
+            let alive = self.pid.is_none_or(is_alive);
+            for idx in 0..self.files.len() {
+                self.check(idx, writer)?;
+            }
+            writer.flush()?;
+
+            if !alive {
+                return Ok(());
+            }
+            if !self.retry && self.files.iter().all(|followed| followed.file.is_none()) {
+                anyhow::bail!("no files remaining");
+            }
+            self.watcher.wait(self.sleep_interval);
+        }
+    }
+
+    /// 파일에 추가된 내용을 출력한다.
+    /// 이름으로 따라갈 때는 교체된 파일을 다시 연다.
+    fn check<W: Write>(&mut self, idx: usize, writer: &mut W) -> Result<(), io::Error> {
+        // 교체되기 전의 파일에 남은 내용을 먼저 출력한다.
+        self.print_appended(idx, writer)?;
+        let reopen = match self.mode {
+            FollowMode::Name => true,
+            FollowMode::Descriptor => self.retry && self.files[idx].file.is_none(),
+        };
+        if reopen && self.reopen(idx) {
+            self.print_appended(idx, writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn print_appended<W: Write>(&mut self, idx: usize, writer: &mut W) -> Result<(), io::Error> {
+        let followed = &mut self.files[idx];
+        let Some(file) = &mut followed.file else {
+            return Ok(());
+        };
+
+        let len = file.metadata()?.len();
+        if len < followed.pos {
+            eprintln!("{}: file truncated", followed.name);
+            followed.pos = 0;
+        }
+        if len == followed.pos {
+            return Ok(());
+        }
+
+        if let Some(last_printed) = &mut self.headers
+            && *last_printed != Some(idx)
+        {
+            if last_printed.is_some() {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "==> {} <==", followed.name)?;
+            *last_printed = Some(idx);
+        }
+        file.seek(SeekFrom::Start(followed.pos))?;
+        followed.pos += io::copy(file, writer)?;
+
+        Ok(())
+    }
+
+    /// 같은 이름의 다른 파일이 생겼으면 새 파일을 연다.
+    /// 새 파일을 열었으면 `true`를 반환한다.
+    fn reopen(&mut self, idx: usize) -> bool {
+        let followed = &mut self.files[idx];
+        let meta = match fs::metadata(&followed.name) {
+            Ok(meta) => meta,
+            Err(e) => {
+                if followed.file.take().is_some() {
+                    eprintln!("'{}' has become inaccessible: {e}", followed.name);
+                    followed.id = None;
+                }
+                return false;
+            }
+        };
+        if followed.id == Some(file_id(&meta)) {
+            return false;
+        }
+
+        let file = match File::open(&followed.name) {
+            Ok(file) => file,
+            Err(e) => {
+                if followed.file.take().is_some() {
+                    eprintln!("'{}' has become inaccessible: {e}", followed.name);
+                    followed.id = None;
+                }
+                return false;
+            }
+        };
+        if followed.file.is_some() {
+            eprintln!("'{}' has been replaced;  following new file", followed.name);
+        } else {
+            eprintln!("'{}' has appeared;  following new file", followed.name);
+        }
+        followed.file = Some(file);
+        followed.id = Some(file_id(&meta));
+        followed.pos = 0;
+        self.watcher.watch(Path::new(&followed.name));
+
+        true
+    }
+}
+
+/// 신호를 보내지 않고 프로세스가 있는지만 확인한다.
+/// 권한이 없어서 실패하면 프로세스는 있는 것이다.
+fn is_alive(pid: i32) -> bool {
+    !matches!(signal::kill(Pid::from_raw(pid), None), Err(Errno::ESRCH))
+}
+
+/// 파일이 바뀔 때까지 기다린다.
+/// inotify를 사용할 수 없으면 `sleep_interval`마다 확인한다.
+enum Watcher {
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::Inotify),
+    Poll,
+}
+
+impl Watcher {
+    fn new(disable_inotify: bool) -> Watcher {
+        #[cfg(target_os = "linux")]
+        if !disable_inotify && let Ok(inotify) = inotify::Inotify::init() {
+            return Watcher::Inotify(inotify);
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = disable_inotify;
+
+        Watcher::Poll
+    }
+
+    /// 파일과 파일이 있는 디렉터리를 감시한다.
+    /// 디렉터리는 파일이 다시 만들어지는 것을 알기 위해 감시한다.
+    fn watch(&mut self, path: &Path) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Watcher::Inotify(inotify) => {
+                use inotify::WatchMask;
+
+                // 감시할 수 없는 파일은 `sleep_interval`마다 확인하므로 오류를 무시한다.
+                let _ = inotify.watches().add(
+                    path,
+                    WatchMask::MODIFY
+                        | WatchMask::ATTRIB
+                        | WatchMask::DELETE_SELF
+                        | WatchMask::MOVE_SELF,
+                );
+                let dir = match path.parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir,
+                    _ => Path::new("."),
+                };
+                let _ = inotify.watches().add(
+                    dir,
+                    WatchMask::CREATE | WatchMask::MOVED_TO | WatchMask::DELETE,
+                );
+            }
+            Watcher::Poll => {}
+        }
+    }
+
+    /// 이벤트가 오거나 `timeout`이 지날 때까지 기다린다.
+    /// 어느 파일의 이벤트인지는 구분하지 않고 모든 파일을 다시 확인한다.
+    fn wait(&mut self, timeout: Duration) {
+        match self {
+            #[cfg(target_os = "linux")]
+            Watcher::Inotify(inotify) => {
+                use std::os::fd::AsFd;
+
+                use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
+
+                let timeout = PollTimeout::try_from(timeout).unwrap_or(PollTimeout::MAX);
+                let mut fds = [PollFd::new(inotify.as_fd(), PollFlags::POLLIN)];
+                // 시그널로 깨어나도 파일을 다시 확인하면 되므로 오류를 무시한다.
+                let _ = poll(&mut fds, timeout);
+
+                let mut buffer = [0; 4096];
+                while inotify
+                    .read_events(&mut buffer)
+                    .is_ok_and(|mut events| events.next().is_some())
+                {}
+            }
+            Watcher::Poll => thread::sleep(timeout),
+        }
+    }
+}
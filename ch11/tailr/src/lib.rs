@@ -1,9 +1,16 @@
-use std::io::{self, BufRead, Seek, SeekFrom, Write};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    mem,
+    os::fd::AsFd,
+};
 
 use args::{Args, TakeValue};
 use clap::Parser;
 
 mod args;
+mod follow;
 
 pub fn run() -> Result<(), anyhow::Error> {
     let args = Args::parse();
@@ -15,44 +22,102 @@ pub fn run_derive() -> Result<(), anyhow::Error> {
     args.run()
 }
 
-/// 책의 코드가 더 효율적으로 보이므로 교체한다.
-fn count_lines_bytes<T: BufRead>(f: &mut T) -> Result<(i64, i64), io::Error> {
-    let mut num_lines = 0;
-    let mut num_bytes = 0;
-    let mut buf = Vec::new();
+/// `-`는 표준 입력이다.
+/// 표준 입력도 `File`로 열어서 파일로 리다이렉트했을 때는 위치를 옮길 수 있게 한다.
+fn open(filename: &str) -> Result<File, io::Error> {
+    match filename {
+        "-" => Ok(File::from(io::stdin().as_fd().try_clone_to_owned()?)),
+        _ => File::open(filename),
+    }
+}
 
-    loop {
-        let len = f.read_until(b'\n', &mut buf)?;
-        if len == 0 {
-            break;
+/// 파일의 끝에서부터 거꾸로 읽는 블록의 크기
+const BLOCK_SIZE: u64 = 8192;
+
+/// 마지막 `num`줄이 시작하는 위치를 찾는다.
+/// 파일의 끝에서부터 블록 단위로 거꾸로 읽으므로 파일 전체를 읽지 않는다.
+/// 줄은 `delimiter`로 끝난다.
+/// 현재 위치를 입력의 시작으로 보므로 앞에서 읽은 표준 입력의 내용은 세지 않는다.
+fn find_last_lines<T: Read + Seek>(f: &mut T, num: u64, delimiter: u8) -> Result<u64, io::Error> {
+    let begin = f.stream_position()?;
+    let len = f.seek(SeekFrom::End(0))?;
+    if num == 0 || len <= begin {
+        return Ok(len);
+    }
+
+    // 파일 끝의 개행은 마지막 줄의 끝이므로 세지 않는다.
+    let mut last = [0];
+    f.seek(SeekFrom::End(-1))?;
+    f.read_exact(&mut last)?;
+    let mut end = if last[0] == delimiter { len - 1 } else { len };
+
+    let mut buffer = vec![0; BLOCK_SIZE as usize];
+    let mut count = 0;
+    while end > begin {
+        let start = end.saturating_sub(BLOCK_SIZE).max(begin);
+        let block = &mut buffer[..(end - start) as usize];
+        f.seek(SeekFrom::Start(start))?;
+        f.read_exact(block)?;
+
+        for (idx, _) in block
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, b)| **b == delimiter)
+        {
+            count += 1;
+            if count == num {
+                return Ok(start + idx as u64 + 1);
+            }
         }
-        num_lines += 1;
-        num_bytes += len as i64;
-        buf.clear();
+        end = start;
     }
 
-    Ok((num_lines, num_bytes))
+    // 파일의 줄 수가 `num`보다 적다.
+    Ok(begin)
 }
 
-/// 코드를 명확하게 정리했다.
+/// `+K`는 현재 위치부터 줄을 세고, `-K`는 끝에서부터 거꾸로 찾는다.
+/// 출력을 마치면 파일 디스크립터는 파일의 끝을 가리킨다.
 fn print_lines<T: BufRead + Seek, U: Write>(
     f: &mut T,
-    start_idx: Option<u64>,
+    take_value: &TakeValue,
+    delimiter: u8,
     writer: &mut U,
 ) -> Result<(), io::Error> {
-    // 파일 디스크립터가 처음을 가리키도록 설정한다.
-    f.seek(SeekFrom::Start(0))?;
-
-    if let Some(start_index) = start_idx {
-        // `start_idx`만큼의 줄을 소비한다.
-        f.lines().take(start_index as usize).for_each(|_| {});
-
-        // 0이 반환되면 EOF이다.
-        // 파일 끝을 유지하기 위해서 `read_line`을 사용한다.
-        let mut line = String::new();
-        while f.read_line(&mut line)? != 0 {
-            write!(writer, "{line}")?;
-            line.clear();
+    match *take_value {
+        TakeValue::TakeNum(0) => {
+            f.seek(SeekFrom::End(0))?;
+            return Ok(());
+        }
+        TakeValue::TakeNum(num) if num < 0 => {
+            let start = find_last_lines(f, num.unsigned_abs(), delimiter)?;
+            f.seek(SeekFrom::Start(start))?;
+        }
+        // `+K`는 K번째 줄부터 출력하므로 K - 1줄을 소비한다.
+        _ => {
+            let skip = match *take_value {
+                TakeValue::TakeNum(num) => num - 1,
+                TakeValue::PlusZero => 0,
+            };
+            skip_lines(f, skip as u64, delimiter)?;
+        }
+    }
+
+    // 바이트를 그대로 복사하므로 UTF-8이 아니어도 된다.
+    io::copy(f, writer)?;
+
+    Ok(())
+}
+
+/// `num`줄을 읽고 버린다.
+/// 줄이 모자라면 EOF에서 멈춘다.
+fn skip_lines<T: BufRead>(f: &mut T, num: u64, delimiter: u8) -> Result<(), io::Error> {
+    let mut line = Vec::new();
+    for _ in 0..num {
+        line.clear();
+        if f.read_until(delimiter, &mut line)? == 0 {
+            break;
         }
     }
 
@@ -69,12 +134,94 @@ fn print_bytes<T: BufRead + Seek, U: Write>(
 ) -> Result<(), io::Error> {
     if let Some(start_idx) = start_idx {
         f.seek(SeekFrom::Start(start_idx))?;
-        let mut buffer = Vec::new();
-        f.read_to_end(&mut buffer)?;
-        if !buffer.is_empty() {
-            write!(writer, "{}", String::from_utf8_lossy(&buffer))?;
+        // 버퍼에 모으지 않고 바로 출력한다.
+        io::copy(f, writer)?;
+    } else {
+        // 따라갈 때는 파일의 끝부터 읽어야 한다.
+        f.seek(SeekFrom::End(0))?;
+    }
+
+    Ok(())
+}
+
+/// 파이프처럼 위치를 옮길 수 없는 입력에서 줄을 출력한다.
+/// `-K`는 마지막 K줄만 큐에 남기면서 끝까지 읽는다.
+fn stream_lines<T: BufRead, U: Write>(
+    f: &mut T,
+    take_value: &TakeValue,
+    delimiter: u8,
+    writer: &mut U,
+) -> Result<(), io::Error> {
+    let num = match *take_value {
+        TakeValue::TakeNum(0) => return Ok(()),
+        TakeValue::TakeNum(num) if num < 0 => num.unsigned_abs(),
+        TakeValue::TakeNum(num) => {
+            skip_lines(f, num as u64 - 1, delimiter)?;
+            io::copy(f, writer)?;
+            return Ok(());
+        }
+        TakeValue::PlusZero => {
+            io::copy(f, writer)?;
+            return Ok(());
+        }
+    };
+
+    let mut lines = VecDeque::new();
+    let mut line = Vec::new();
+    while f.read_until(delimiter, &mut line)? != 0 {
+        lines.push_back(mem::take(&mut line));
+        // 큐가 넘치면 가장 오래된 줄의 버퍼를 다시 사용한다.
+        if lines.len() as u64 > num
+            && let Some(oldest) = lines.pop_front()
+        {
+            line = oldest;
+            line.clear();
+        }
+    }
+    for line in lines {
+        writer.write_all(&line)?;
+    }
+
+    Ok(())
+}
+
+/// 파이프처럼 위치를 옮길 수 없는 입력에서 바이트를 출력한다.
+/// `-K`는 마지막 K바이트만 남기면서 끝까지 읽는다.
+fn stream_bytes<T: BufRead, U: Write>(
+    f: &mut T,
+    take_value: &TakeValue,
+    writer: &mut U,
+) -> Result<(), io::Error> {
+    let num = match *take_value {
+        TakeValue::TakeNum(0) => return Ok(()),
+        TakeValue::TakeNum(num) if num < 0 => num.unsigned_abs(),
+        TakeValue::TakeNum(num) => {
+            io::copy(&mut f.take(num as u64 - 1), &mut io::sink())?;
+            io::copy(f, writer)?;
+            return Ok(());
+        }
+        TakeValue::PlusZero => {
+            io::copy(f, writer)?;
+            return Ok(());
+        }
+    };
+
+    let mut bytes = VecDeque::new();
+    loop {
+        let buffer = f.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        let len = buffer.len();
+        bytes.extend(buffer);
+        f.consume(len);
+        if bytes.len() as u64 > num {
+            bytes.drain(..bytes.len() - num as usize);
         }
     }
+    let (front, back) = bytes.as_slices();
+    writer.write_all(front)?;
+    writer.write_all(back)?;
 
     Ok(())
 }
@@ -117,23 +264,79 @@ fn get_start_index(take_value: &TakeValue, total_lines: i64) -> Option<u64> {
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, io::BufReader};
+    use std::io::{BufReader, Cursor};
 
     use super::*;
 
     #[test]
-    fn test_count_lines_bytes() {
-        let res = count_lines_bytes(&mut BufReader::new(
-            File::open("tests/inputs/one.txt").unwrap(),
-        ));
-        assert!(res.is_ok());
-        pretty_assertions::assert_eq!(res.unwrap(), (1, 24));
-
-        let res = count_lines_bytes(&mut BufReader::new(
-            File::open("tests/inputs/twelve.txt").unwrap(),
-        ));
-        assert!(res.is_ok());
-        pretty_assertions::assert_eq!(res.unwrap(), (12, 63));
+    fn test_find_last_lines() {
+        let find =
+            |text: &[u8], num: u64| find_last_lines(&mut Cursor::new(text), num, b'\n').unwrap();
+
+        pretty_assertions::assert_eq!(find(b"a\nb\nc\n", 1), 4);
+        pretty_assertions::assert_eq!(find(b"a\nb\nc\n", 2), 2);
+        pretty_assertions::assert_eq!(find(b"a\nb\nc\n", 3), 0);
+        pretty_assertions::assert_eq!(find(b"a\nb\nc\n", 4), 0);
+        pretty_assertions::assert_eq!(find(b"a\nb\nc\n", 0), 6);
+        // 마지막 줄에 개행이 없어도 한 줄이다.
+        pretty_assertions::assert_eq!(find(b"a\nb\nc", 1), 4);
+        // 빈 줄도 한 줄이다.
+        pretty_assertions::assert_eq!(find(b"a\n\n\n", 2), 2);
+        pretty_assertions::assert_eq!(find(b"", 1), 0);
+
+        // 여러 블록에 걸친 줄도 찾는다.
+        let text: Vec<u8> = (0..3000)
+            .flat_map(|n| format!("line {n}\n").into_bytes())
+            .collect();
+        let start = find(&text, 1000);
+        pretty_assertions::assert_eq!(&text[start as usize..][..10], b"line 2000\n");
+        let long_line = [vec![b'x'; BLOCK_SIZE as usize * 3], b"\nlast\n".to_vec()].concat();
+        pretty_assertions::assert_eq!(find(&long_line, 2), 0);
+        pretty_assertions::assert_eq!(find(&long_line, 1), BLOCK_SIZE * 3 + 1);
+
+        // -z이면 NUL로 끝나는 레코드를 센다.
+        let find_zero =
+            |text: &[u8], num: u64| find_last_lines(&mut Cursor::new(text), num, b'\0').unwrap();
+        pretty_assertions::assert_eq!(find_zero(b"a\nb\0c\0", 1), 4);
+        pretty_assertions::assert_eq!(find_zero(b"a\nb\0c\0", 2), 0);
+
+        // 현재 위치 앞의 줄은 세지 않는다.
+        let find_from = |text: &[u8], pos: u64, num: u64| {
+            let mut f = Cursor::new(text);
+            f.set_position(pos);
+            find_last_lines(&mut f, num, b'\n').unwrap()
+        };
+        pretty_assertions::assert_eq!(find_from(b"a\nb\nc\n", 2, 5), 2);
+        pretty_assertions::assert_eq!(find_from(b"a\nb\nc\n", 2, 1), 4);
+        pretty_assertions::assert_eq!(find_from(b"a\nb\nc\n", 6, 1), 6);
+    }
+
+    #[test]
+    fn test_stream() {
+        let lines = |text: &str, take_value: TakeValue| {
+            let mut output = Vec::new();
+            stream_lines(&mut text.as_bytes(), &take_value, b'\n', &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let bytes = |text: &str, take_value: TakeValue| {
+            let mut output = Vec::new();
+            // 버퍼보다 긴 입력도 나누어 읽는다.
+            let mut f = BufReader::with_capacity(2, text.as_bytes());
+            stream_bytes(&mut f, &take_value, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        pretty_assertions::assert_eq!(lines("a\nb\nc", TakeValue::TakeNum(-2)), "b\nc");
+        pretty_assertions::assert_eq!(lines("a\nb\nc\n", TakeValue::TakeNum(-5)), "a\nb\nc\n");
+        pretty_assertions::assert_eq!(lines("a\nb\nc\n", TakeValue::TakeNum(2)), "b\nc\n");
+        pretty_assertions::assert_eq!(lines("a\nb\nc\n", TakeValue::TakeNum(0)), "");
+        pretty_assertions::assert_eq!(lines("a\n", TakeValue::PlusZero), "a\n");
+
+        pretty_assertions::assert_eq!(bytes("abcdefg", TakeValue::TakeNum(-3)), "efg");
+        pretty_assertions::assert_eq!(bytes("abcdefg", TakeValue::TakeNum(-10)), "abcdefg");
+        pretty_assertions::assert_eq!(bytes("abcdefg", TakeValue::TakeNum(3)), "cdefg");
+        pretty_assertions::assert_eq!(bytes("abcdefg", TakeValue::TakeNum(10)), "");
+        pretty_assertions::assert_eq!(bytes("abcdefg", TakeValue::PlusZero), "abcdefg");
     }
 
     #[test]
@@ -1,11 +1,15 @@
 use anyhow::Result;
-use assert_cmd::Command;
+use assert_cmd::{Command, cargo::CommandCargoExt};
 use predicates::prelude::*;
 use pretty_assertions::assert_eq;
 use rand::Rng;
 use rand::distr::Alphanumeric;
 use std::fs::{self, File};
 use std::io::Read;
+use std::path::Path;
+use std::process::{self, Output, Stdio};
+use std::thread;
+use std::time::Duration;
 
 const PRG: &str = "tailr";
 const EMPTY: &str = "tests/inputs/empty.txt";
@@ -13,6 +17,7 @@ const ONE: &str = "tests/inputs/one.txt";
 const TWO: &str = "tests/inputs/two.txt";
 const THREE: &str = "tests/inputs/three.txt";
 const TWELVE: &str = "tests/inputs/twelve.txt";
+const RECORDS: &str = "tests/inputs/records.bin";
 
 // --------------------------------------------------
 fn random_string() -> String {
@@ -33,17 +38,6 @@ fn gen_bad_file() -> String {
     }
 }
 
-// --------------------------------------------------
-#[test]
-fn dies_no_args() -> Result<()> {
-    Command::cargo_bin(PRG)?
-        .assert()
-        .failure()
-        .stderr(predicate::str::contains("Usage"));
-
-    Ok(())
-}
-
 // --------------------------------------------------
 #[test]
 fn dies_bad_bytes() -> Result<()> {
@@ -829,3 +823,390 @@ fn multiple_files_c_plus_3() -> Result<()> {
         "tests/expected/all.c+3.out",
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn one_verbose() -> Result<()> {
+    run(&["-v", "-n", "1", ONE], "tests/expected/one.txt.v.n1.out")
+}
+
+#[test]
+fn verbose_overrides_quiet() -> Result<()> {
+    run(
+        &["-q", "--verbose", "-n", "1", ONE, TWO],
+        "tests/expected/all.q.v.n1.out",
+    )
+}
+
+#[test]
+fn quiet_overrides_verbose() -> Result<()> {
+    run(
+        &["-v", "-q", "-n", "1", TWELVE, EMPTY, ONE, THREE, TWO],
+        "tests/expected/all.n1.q.out",
+    )
+}
+
+#[test]
+fn twelve_c1b() -> Result<()> {
+    run(&["-c", "1b", TWELVE], "tests/expected/twelve.txt.c1b.out")
+}
+
+#[test]
+fn twelve_n_plus_1k() -> Result<()> {
+    run(&["-n", "+1k", TWELVE], "tests/expected/empty.txt.out")
+}
+
+#[test]
+fn dies_lines_too_large() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "1Z", ONE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "illegal line count -- 1Z: value too large",
+        ));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+/// UTF-8이 아닌 출력도 바이트 그대로 비교한다.
+fn run_bytes(args: &[&str], expected_file: &str) -> Result<()> {
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, fs::read(expected_file)?);
+
+    Ok(())
+}
+
+#[test]
+fn records_n4() -> Result<()> {
+    run_bytes(&["-n", "4", RECORDS], "tests/expected/records.bin.n4.out")
+}
+
+#[test]
+fn records_c20() -> Result<()> {
+    run_bytes(&["-c", "20", RECORDS], "tests/expected/records.bin.c20.out")
+}
+
+#[test]
+fn records_z_n2() -> Result<()> {
+    run_bytes(
+        &["-z", "-n", "2", RECORDS],
+        "tests/expected/records.bin.z.n2.out",
+    )
+}
+
+#[test]
+fn records_z_n_plus_2() -> Result<()> {
+    run_bytes(
+        &["--zero-terminated", "-n", "+2", RECORDS],
+        "tests/expected/records.bin.z.n+2.out",
+    )
+}
+
+#[test]
+fn stdin_records_z_n2() -> Result<()> {
+    run_stdin(
+        RECORDS,
+        &["-z", "-n", "2"],
+        "tests/expected/records.bin.z.n2.out",
+    )
+}
+
+// --------------------------------------------------
+/// 파이프로 `input`을 표준 입력에 넣는다.
+fn run_stdin(input: &str, args: &[&str], expected_file: &str) -> Result<()> {
+    let output = Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(fs::read(input)?)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, fs::read(expected_file)?);
+
+    Ok(())
+}
+
+#[test]
+fn stdin_no_args() -> Result<()> {
+    run_stdin(TWELVE, &[], "tests/expected/twelve.txt.out")
+}
+
+#[test]
+fn stdin_n3() -> Result<()> {
+    run_stdin(
+        TWELVE,
+        &["-n", "3", "-"],
+        "tests/expected/twelve.txt.n3.out",
+    )
+}
+
+#[test]
+fn stdin_n200() -> Result<()> {
+    run_stdin(TWELVE, &["-n", "200"], "tests/expected/twelve.txt.n200.out")
+}
+
+#[test]
+fn stdin_n_plus_2() -> Result<()> {
+    run_stdin(TWELVE, &["-n", "+2"], "tests/expected/twelve.txt.n+2.out")
+}
+
+#[test]
+fn stdin_c8() -> Result<()> {
+    run_stdin(THREE, &["-c", "8"], "tests/expected/three.txt.c8.out")
+}
+
+#[test]
+fn stdin_c_plus_2() -> Result<()> {
+    run_stdin(THREE, &["-c", "+2"], "tests/expected/three.txt.c+2.out")
+}
+
+#[test]
+fn stdin_empty() -> Result<()> {
+    run_stdin(EMPTY, &["-c", "3"], "tests/expected/empty.txt.c3.out")
+}
+
+#[test]
+fn stdin_with_files() -> Result<()> {
+    run_stdin(TWO, &["-n", "1", ONE, "-"], "tests/expected/stdin.n1.out")
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(target_os = "linux")]
+fn proc_file() -> Result<()> {
+    // `/proc`의 파일은 크기가 0인 일반 파일이므로 끝까지 읽어서 찾는다.
+    const PROC: &str = "/proc/filesystems";
+    let text = fs::read(PROC)?;
+    let last_lines = text[..text.len() - 1]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .and_then(|pos| text[..pos].iter().rposition(|&b| b == b'\n'))
+        .map_or(0, |pos| pos + 1);
+
+    for (args, expected) in [
+        (["-n", "2"], &text[last_lines..]),
+        (["-c", "20"], &text[text.len() - 20..]),
+        (
+            ["-n", "+2"],
+            &text[text.iter().position(|&b| b == b'\n').unwrap() + 1..],
+        ),
+    ] {
+        let output = Command::cargo_bin(PRG)?.args(args).arg(PROC).output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stdout, expected);
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin_redirected_file() -> Result<()> {
+    // 파일로 리다이렉트한 표준 입력은 위치를 옮겨서 읽는다.
+    let output = process::Command::cargo_bin(PRG)?
+        .args(["-n", "3"])
+        .stdin(File::open(TWELVE)?)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, fs::read("tests/expected/twelve.txt.n3.out")?);
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin_redirected_file_offset() -> Result<()> {
+    use std::io::{Seek, SeekFrom};
+
+    // 앞에서 읽은 부분은 입력에 들어가지 않는다.
+    let text = fs::read(THREE)?;
+    let offset = text.iter().position(|&b| b == b'\n').unwrap() + 1;
+    for args in [["-n", "+1"], ["-n", "200"], ["-c", "200"]] {
+        let mut stdin = File::open(THREE)?;
+        stdin.seek(SeekFrom::Start(offset as u64))?;
+        let output = process::Command::cargo_bin(PRG)?
+            .args(args)
+            .stdin(stdin)
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(output.stdout, &text[offset..]);
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_stdin_pipe() -> Result<()> {
+    // 파이프는 따라가지 않고 끝낸다.
+    Command::cargo_bin(PRG)?
+        .args(["-f", "-n", "1"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("b\n");
+
+    Ok(())
+}
+
+#[test]
+fn dies_follow_stdin_by_name() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-F", "-"])
+        .write_stdin("a\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot follow '-' by name"));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_sleep_interval() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "-s", "abc", ONE])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid number of seconds: 'abc'"));
+
+    Ok(())
+}
+
+// --------------------------------------------------
+/// `dir`에서 `tailr`를 실행하고 `change`로 파일을 바꾼다.
+/// `--pid`로 지정한 프로세스가 끝나면 `tailr`도 끝난다.
+fn run_follow(
+    args: &[&str],
+    dir: &Path,
+    first: &str,
+    change: impl FnOnce() -> Result<()>,
+) -> Result<Output> {
+    let mut sleep = process::Command::new("sleep").arg("2").spawn()?;
+    let pid = sleep.id().to_string();
+    let mut child = process::Command::cargo_bin(PRG)?
+        .args(args)
+        .args(["-s", "0.1", "--pid", &pid])
+        .current_dir(dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // 처음 출력이 나온 뒤에 파일을 바꾼다.
+    let mut stdout = child.stdout.take().unwrap();
+    let mut buffer = vec![0; first.len()];
+    stdout.read_exact(&mut buffer)?;
+    assert_eq!(String::from_utf8_lossy(&buffer), first);
+    change()?;
+    sleep.wait()?;
+
+    stdout.read_to_end(&mut buffer)?;
+    let mut output = child.wait_with_output()?;
+    output.stdout = buffer;
+    Ok(output)
+}
+
+/// 파일 끝에 내용을 추가한다.
+fn append(path: &Path, text: &str) -> Result<()> {
+    use std::io::Write;
+
+    fs::OpenOptions::new()
+        .append(true)
+        .open(path)?
+        .write_all(text.as_bytes())?;
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_appended() -> Result<()> {
+    // inotify를 사용할 때와 주기적으로 확인할 때의 결과가 같아야 한다.
+    for extra in [None, Some("--disable-inotify")] {
+        let dir = tempfile::tempdir()?;
+        let log = dir.path().join("log");
+        fs::write(&log, "one\ntwo\nthree\n")?;
+
+        let mut args = vec!["-f", "-n", "2", "log"];
+        args.extend(extra);
+        let output = run_follow(&args, dir.path(), "two\nthree\n", || append(&log, "four\n"))?;
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout)?, "two\nthree\nfour\n");
+    }
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_headers() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let (a, b) = (dir.path().join("a"), dir.path().join("b"));
+    fs::write(&a, "a1\n")?;
+    fs::write(&b, "b1\n")?;
+
+    // 다른 파일의 내용을 출력할 때마다 헤더를 출력한다.
+    let output = run_follow(
+        &["-f", "a", "b"],
+        dir.path(),
+        "==> a <==\na1\n\n==> b <==\nb1\n",
+        || {
+            append(&a, "a2\n")?;
+            thread::sleep(Duration::from_millis(300));
+            append(&b, "b2\n")
+        },
+    )?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "==> a <==\na1\n\n==> b <==\nb1\n\n==> a <==\na2\n\n==> b <==\nb2\n"
+    );
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_name_rotation() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("log");
+    fs::write(&log, "old\n")?;
+
+    let output = run_follow(&["-F", "log"], dir.path(), "old\n", || {
+        fs::rename(&log, dir.path().join("log.1"))?;
+        thread::sleep(Duration::from_millis(300));
+        fs::write(&log, "new\n")?;
+        thread::sleep(Duration::from_millis(300));
+        // 크기가 줄어들면 처음부터 다시 읽는다.
+        fs::write(&log, "")?;
+        thread::sleep(Duration::from_millis(300));
+        append(&log, "truncated\n")
+    })?;
+    assert_eq!(String::from_utf8(output.stdout)?, "old\nnew\ntruncated\n");
+    assert_eq!(
+        String::from_utf8(output.stderr)?,
+        "'log' has become inaccessible: No such file or directory (os error 2)\n\
+         'log' has appeared;  following new file\n\
+         log: file truncated\n"
+    );
+
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn follow_descriptor_keeps_file() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("log");
+    fs::write(&log, "old\n")?;
+
+    // 이름이 바뀌어도 처음 연 파일을 계속 읽는다.
+    let output = run_follow(&["-f", "log"], dir.path(), "old\n", || {
+        let rotated = dir.path().join("log.1");
+        fs::rename(&log, &rotated)?;
+        fs::write(&log, "new\n")?;
+        append(&rotated, "more\n")
+    })?;
+    assert_eq!(String::from_utf8(output.stdout)?, "old\nmore\n");
+
+    Ok(())
+}